@@ -1,11 +1,14 @@
 use crate::matrix::GaussianElimination;
 use crate::parser;
-use crate::parser::ChemicalEquation;
 use crate::parser::Side;
+use crate::parser::{to_signed, ChemicalEquation, Compound};
+use crate::predict;
+use indexmap::IndexMap;
 use na::DMatrix;
 use num::rational::Ratio;
-use num::{Integer, ToPrimitive};
+use num::{CheckedAdd, CheckedMul, Integer, Signed, ToPrimitive, Zero};
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 use std::ops::Mul;
 
 impl Mul<&Side> for Ratio<isize> {
@@ -18,11 +21,35 @@ impl Mul<&Side> for Ratio<isize> {
     }
 }
 
+/// How the coefficients of a balanced equation are scaled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Scale {
+    /// The coefficients the equation was written with if they balance, otherwise the
+    /// smallest whole numbers.
+    AsWritten,
+    /// The smallest whole numbers, e.g. 2H2 + O2 = 2H2O.
+    Integers,
+    /// Per mole of the species at this index, as in enthalpies of formation and combustion,
+    /// e.g. H2 + 1/2 O2 = H2O for water.
+    PerSpecies(usize),
+}
+
 pub struct Balancer;
 
 impl Balancer {
+    pub fn balance(equation: &str) -> Result<ChemicalEquation, String> {
+        balance(predict::complete(parser::parse(equation)?)?)
+    }
+    pub(crate) fn balance_with(equation: &str, scale: Scale) -> Result<ChemicalEquation, String> {
+        Ok(rescale(Balancer::balance(equation)?, scale))
+    }
     pub fn balance_real_time(equation: &str) -> ChemicalEquation {
         let eq = parser::parse(equation).unwrap_or(ChemicalEquation::empty());
+        let eq = if predict::awaits_products(&eq) {
+            predict::complete(eq.clone()).unwrap_or(eq)
+        } else {
+            eq
+        };
         if let Ok(bal_eq) = balance(eq.clone()) {
             bal_eq
         } else {
@@ -31,7 +58,102 @@ impl Balancer {
     }
 }
 
+/// An element, or the charge, whose total differs between the two sides of an equation
+/// as written.
+#[derive(Debug, Clone)]
+pub(crate) struct Imbalance {
+    pub(crate) element: String,
+    pub(crate) reactants: Ratio<isize>,
+    pub(crate) products: Ratio<isize>,
+}
+
+impl Display for Imbalance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let difference = self.products - self.reactants;
+        let side = if difference.is_positive() {
+            "products"
+        } else {
+            "reactants"
+        };
+        write!(
+            f,
+            "{}: {} on the left, {} on the right ({} too many in the {})",
+            self.element,
+            self.reactants,
+            self.products,
+            difference.abs(),
+            side
+        )
+    }
+}
+
+/// Scales the coefficients of a balanced equation. A species index past the end of the
+/// equation leaves it as it is.
+pub(crate) fn rescale(mut eq: ChemicalEquation, scale: Scale) -> ChemicalEquation {
+    let coefficients = eq.terms.iter().map(|cpd| cpd.coefficient);
+    let divisor = match scale {
+        Scale::AsWritten => return eq,
+        Scale::Integers => Ratio::new(
+            coefficients.clone().fold(0, |gcd, c| gcd.gcd(c.numer())),
+            coefficients.fold(1, |lcm, c| lcm.lcm(c.denom())),
+        ),
+        Scale::PerSpecies(i) => match eq.terms.get(i) {
+            Some(cpd) => cpd.coefficient,
+            None => return eq,
+        },
+    };
+    if divisor.is_zero() {
+        return eq;
+    }
+    for cpd in &mut eq.terms {
+        cpd.coefficient /= divisor;
+    }
+    eq
+}
+
+/// Checks the coefficients `eq` was written with, returning every element (and the charge)
+/// that does not balance. An empty list means the equation is balanced as written.
+pub(crate) fn check(eq: &ChemicalEquation) -> Result<Vec<Imbalance>, String> {
+    let mut totals: IndexMap<String, [Ratio<isize>; 2]> = IndexMap::new();
+    for (i, cpd) in eq.terms.iter().enumerate() {
+        let side = usize::from(i >= eq.rhs_ix);
+        let coefficient = to_signed(cpd.coefficient);
+        let counts = cpd
+            .elements
+            .iter()
+            .map(|(elem, count)| (elem.clone(), to_signed(*count)))
+            .chain((cpd.charge != 0).then(|| ("charge".to_string(), cpd.charge.into())));
+        for (elem, count) in counts {
+            let too_large = || match elem.as_str() {
+                "charge" => "the total charge is too large".to_string(),
+                elem => format!("the number of {} atoms is too large", elem),
+            };
+            let total = &mut totals.entry(elem.clone()).or_default()[side];
+            *total = coefficient
+                .checked_mul(&count)
+                .and_then(|amount| total.checked_add(&amount))
+                .ok_or_else(too_large)?;
+        }
+    }
+    Ok(totals
+        .into_iter()
+        .filter(|(_, [reactants, products])| reactants != products)
+        .map(|(element, [reactants, products])| Imbalance {
+            element,
+            reactants,
+            products,
+        })
+        .collect())
+}
+
+/// Solves for the coefficients of `eq`, unless the ones it was written with already balance,
+/// in which case they are kept (so `H2 + 1/2 O2 = H2O` stays as written).
 fn balance(eq: ChemicalEquation) -> Result<ChemicalEquation, String> {
+    let (reactants, products) = eq.terms.split_at(eq.rhs_ix);
+    let written = |side: &[Compound]| side.iter().any(|cpd| !cpd.elements.is_empty());
+    if written(reactants) && written(products) && check(&eq)?.is_empty() {
+        return Ok(eq);
+    }
     let mut elements = HashMap::new();
     for cpd in eq.terms.iter() {
         for elem in cpd.elements.keys() {
@@ -43,34 +165,122 @@ fn balance(eq: ChemicalEquation) -> Result<ChemicalEquation, String> {
         .enumerate()
         .map(|(i, (val, _))| (val.clone(), i))
         .collect();
+    // Charge is conserved like an element, so ionic equations get an extra row for it.
+    let charged = eq.terms.iter().any(|cpd| cpd.charge != 0);
+    let rows = elements.len() + charged as usize;
     let mut eq_matrix: DMatrix<Ratio<isize>> =
-        DMatrix::from_element(elements.len(), eq.terms.len(), Ratio::new_raw(0, 1));
-    let mut col = 0usize;
-    for cpd in &eq.terms {
+        DMatrix::from_element(rows, eq.terms.len(), Ratio::new_raw(0, 1));
+    for (col, cpd) in eq.terms.iter().enumerate() {
         for (elem, row) in elements.iter() {
             if let Some(coeff) = cpd.elements.get(elem) {
-                *eq_matrix.get_mut((*row, col)).unwrap() =
-                    Ratio::from_integer(*coeff as isize) * &cpd.side;
+                *eq_matrix.get_mut((*row, col)).unwrap() = to_signed(*coeff) * &cpd.side;
             }
         }
-        col += 1;
+        if charged {
+            *eq_matrix.get_mut((elements.len(), col)).unwrap() =
+                Ratio::from_integer(cpd.charge) * &cpd.side;
+        }
     }
 
     let coeffs = GaussianElimination::new(eq_matrix)
         .solve()?
         .into_iter()
-        .map(|v| {
+        .flat_map(|v| {
             let lcm = v.iter().fold(1, |lcm, ratio| lcm.lcm(ratio.denom()));
-            v.into_iter()
+            let ints = v
+                .into_iter()
                 .map(|ratio| lcm / *ratio.denom() * *ratio.numer())
-                .collect::<Vec<_>>()
+                .collect::<Vec<_>>();
+            let gcd = ints.iter().fold(0, |gcd, int| gcd.gcd(int)).max(1);
+            ints.into_iter().map(|int| int / gcd).collect::<Vec<_>>()
+        })
+        .map(|int| {
+            int.to_usize()
+                .filter(|int| *int > 0)
+                .ok_or("the equation cannot be balanced with positive coefficients")
         })
-        .flatten()
-        .map(|int| int.to_usize().unwrap_or(1))
-        .collect::<Vec<_>>();
+        .collect::<Result<Vec<_>, _>>()?;
     let mut eq = eq;
     for (i, cpd) in eq.terms.iter_mut().enumerate() {
-        cpd.coefficient = coeffs[i].max(1);
+        cpd.coefficient = Ratio::from_integer(coeffs[i]);
+    }
+    if !check(&eq)?.is_empty() {
+        return Err("the equation cannot be balanced".to_string());
     }
     Ok(eq)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coefficients(eq: &ChemicalEquation) -> Vec<Ratio<usize>> {
+        eq.terms.iter().map(|cpd| cpd.coefficient).collect()
+    }
+
+    fn integers(coefficients: &[usize]) -> Vec<Ratio<usize>> {
+        coefficients
+            .iter()
+            .map(|c| Ratio::from_integer(*c))
+            .collect()
+    }
+
+    #[test]
+    fn balances_charge_as_well_as_elements() {
+        let eq = Balancer::balance("MnO4^- + Fe^2+ + H^+ = Mn^2+ + Fe^3+ + H2O").unwrap();
+        assert_eq!(coefficients(&eq), integers(&[1, 5, 8, 1, 5, 4]));
+        assert!(check(&eq).unwrap().is_empty());
+    }
+
+    #[test]
+    fn checks_charge_as_written() {
+        let eq = parser::parse("Fe^3+ + Cu = Fe^2+ + Cu^2+").unwrap();
+        let elements = check(&eq)
+            .unwrap()
+            .into_iter()
+            .map(|imbalance| imbalance.element)
+            .collect::<Vec<_>>();
+        assert_eq!(elements, ["charge"]);
+    }
+
+    #[test]
+    fn keeps_fractional_coefficients_as_written() {
+        let eq = Balancer::balance("H2 + 1/2O2 = H2O").unwrap();
+        let expected = vec![
+            Ratio::from_integer(1),
+            Ratio::new(1, 2),
+            Ratio::from_integer(1),
+        ];
+        assert_eq!(coefficients(&rescale(eq, Scale::AsWritten)), expected);
+    }
+
+    #[test]
+    fn rescales_to_whole_numbers() {
+        let eq = Balancer::balance("H2 + 1/2O2 = H2O").unwrap();
+        assert_eq!(
+            coefficients(&rescale(eq, Scale::Integers)),
+            integers(&[2, 1, 2])
+        );
+    }
+
+    #[test]
+    fn rescales_per_mole_of_a_species() {
+        let eq = Balancer::balance("C2H6 + O2 = CO2 + H2O").unwrap();
+        let per_ethane = rescale(eq.clone(), Scale::PerSpecies(0));
+        let expected = vec![
+            Ratio::from_integer(1),
+            Ratio::new(7, 2),
+            Ratio::from_integer(2),
+            Ratio::from_integer(3),
+        ];
+        assert_eq!(coefficients(&per_ethane), expected);
+        let past_the_end = rescale(eq.clone(), Scale::PerSpecies(4));
+        assert_eq!(coefficients(&past_the_end), coefficients(&eq));
+    }
+
+    #[test]
+    fn rejects_totals_that_overflow() {
+        let eq = parser::parse("4611686018427387904H2 = 4611686018427387904H2").unwrap();
+        assert!(check(&eq).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::isotopes;
+use crate::parser::{ChemicalEquation, Compound};
+use crate::redox;
+use crate::solubility::is_precipitate;
+use num::rational::Ratio;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ReactionType {
+    Combustion,
+    Synthesis,
+    Decomposition,
+    SingleReplacement,
+    DoubleReplacement,
+    Neutralisation,
+    Precipitation,
+    Redox,
+}
+
+impl Display for ReactionType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReactionType::Combustion => "combustion",
+            ReactionType::Synthesis => "synthesis",
+            ReactionType::Decomposition => "decomposition",
+            ReactionType::SingleReplacement => "single replacement",
+            ReactionType::DoubleReplacement => "double replacement",
+            ReactionType::Neutralisation => "acid–base neutralisation",
+            ReactionType::Precipitation => "precipitation",
+            ReactionType::Redox => "redox",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Elements of the first formula unit in the order they were written, so NaOH gives
+/// Na, O, H and CH3COOH gives C, H, C, O, O, H. Isotopes count as their element.
+fn written(cpd: &Compound) -> Vec<&str> {
+    cpd.units
+        .first()
+        .map(|unit| unit.elements().map(|(e, _)| isotopes::element(e)).collect())
+        .unwrap_or_default()
+}
+
+/// Whether `cpd` contains `element`, in any of its isotopes, so [13C]H4 and CD4 contain C and H.
+fn contains(cpd: &Compound, element: &str) -> bool {
+    cpd.elements
+        .keys()
+        .any(|label| isotopes::element(label) == element)
+}
+
+fn is(cpd: &Compound, formula: &[(&str, usize)], charge: isize) -> bool {
+    cpd.charge == charge
+        && cpd.elements.len() == formula.len()
+        && formula
+            .iter()
+            .all(|(e, n)| cpd.elements.get(*e) == Some(&Ratio::from_integer(*n)))
+}
+
+/// A free element such as Zn, O2 or S8.
+fn is_element(cpd: &Compound) -> bool {
+    cpd.elements.len() == 1 && cpd.charge == 0
+}
+
+fn is_water(cpd: &Compound) -> bool {
+    is(cpd, &[("H", 2), ("O", 1)], 0)
+}
+
+/// H+, H3O+, compounds written with H first (HCl, H2SO4) and carboxylic acids.
+fn is_acid(cpd: &Compound) -> bool {
+    let written = written(cpd);
+    is(cpd, &[("H", 1)], 1)
+        || is(cpd, &[("H", 3), ("O", 1)], 1)
+        || (written.first() == Some(&"H")
+            && cpd.elements.len() > 1
+            && cpd.charge == 0
+            && !is_water(cpd)
+            && !is(cpd, &[("H", 2), ("O", 2)], 0))
+        || written.ends_with(&["C", "O", "O", "H"])
+}
+
+/// OH-, NH3 and hydroxides written with OH last (NaOH, NH4OH).
+fn is_base(cpd: &Compound) -> bool {
+    is(cpd, &[("O", 1), ("H", 1)], -1)
+        || is(cpd, &[("N", 1), ("H", 3)], 0)
+        || (cpd.charge == 0
+            && cpd.elements.len() > 2
+            && written(cpd).ends_with(&["O", "H"])
+            && !written(cpd).ends_with(&["C", "O", "O", "H"]))
+}
+
+/// Classifies `eq` by every type it fits. A reaction can fit several,
+/// e.g. burning hydrogen is combustion, synthesis and redox; an empty list means it fits none.
+///
+/// The rules are the ones taught in introductory courses and look only at the formulas as
+/// written: combustion burns a C or H compound in O2 to oxides, synthesis has one product,
+/// decomposition one reactant, single replacement swaps a free element for one in a compound
+/// and double replacement swaps partners between two compounds. Neutralisation needs an acid
+/// and a base making water (or NH3 making a salt) and precipitation a solid product from
+/// reactants that are not solid, going by the phases given or else the solubility rules.
+pub(crate) fn classify(eq: &ChemicalEquation) -> Vec<ReactionType> {
+    let mut types = Vec::new();
+    let (reactants, products) = eq.terms.split_at(eq.rhs_ix);
+    if reactants.is_empty() || products.is_empty() {
+        return types;
+    }
+    let redox = !redox::redox_changes(eq).is_empty();
+    let oxygen = reactants.iter().any(|cpd| is(cpd, &[("O", 2)], 0));
+    let fuel = reactants
+        .iter()
+        .filter(|cpd| !is(cpd, &[("O", 2)], 0))
+        .all(|cpd| contains(cpd, "C") || contains(cpd, "H"));
+    let oxides = products
+        .iter()
+        .all(|cpd| contains(cpd, "O") || is(cpd, &[("N", 2)], 0));
+    if oxygen && fuel && oxides && reactants.len() == 2 {
+        types.push(ReactionType::Combustion);
+    }
+    if reactants.len() > 1 && products.len() == 1 {
+        types.push(ReactionType::Synthesis);
+    }
+    if reactants.len() == 1 && products.len() > 1 {
+        types.push(ReactionType::Decomposition);
+    }
+    if reactants.len() == 2 && products.len() == 2 {
+        let free = |side: &[Compound]| side.iter().filter(|cpd| is_element(cpd)).count();
+        match (free(reactants), free(products)) {
+            (1, 1) => types.push(ReactionType::SingleReplacement),
+            (0, 0) if !redox => types.push(ReactionType::DoubleReplacement),
+            _ => {}
+        }
+    }
+    let acid = reactants.iter().any(is_acid);
+    let base = reactants.iter().any(is_base);
+    let ammonia = reactants
+        .iter()
+        .any(|cpd| is(cpd, &[("N", 1), ("H", 3)], 0));
+    if acid && base && !redox && (products.iter().any(is_water) || ammonia) {
+        types.push(ReactionType::Neutralisation);
+    }
+    if products.iter().any(is_precipitate) && !reactants.iter().any(is_precipitate) && !redox {
+        types.push(ReactionType::Precipitation);
+    }
+    if redox {
+        types.push(ReactionType::Redox);
+    }
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::Balancer;
+
+    fn types(equation: &str) -> Vec<ReactionType> {
+        classify(&Balancer::balance(equation).unwrap())
+    }
+
+    #[test]
+    fn classifies_labelled_combustion() {
+        for equation in [
+            "CH4 + O2 = CO2 + H2O",
+            "^13CH4 + O2 = ^13CO2 + H2O",
+            "CD4 + O2 = CO2 + D2O",
+        ] {
+            assert!(
+                types(equation).contains(&ReactionType::Combustion),
+                "{}",
+                equation
+            );
+        }
+    }
+
+    #[test]
+    fn reads_labelled_carboxylic_acids() {
+        let eq = "^13CH3COOH + NaOH = ^13CH3COONa + H2O";
+        assert!(types(eq).contains(&ReactionType::Neutralisation));
+    }
+}
@@ -0,0 +1,408 @@
+use crate::balance::{self, Balancer, Scale};
+use crate::classify;
+use crate::combustion::{self, CombustionData};
+use crate::composition::{self, DEFAULT_TOLERANCE};
+use crate::equilibrium;
+use crate::export::{self, Format};
+use crate::hess;
+use crate::ionic;
+use crate::mass_spec;
+use crate::names;
+use crate::parser::{self, parse_compound, ChemicalEquation, Compound};
+use crate::redox;
+use crate::stoichiometry::{Reactant, StoichCalculator};
+use crate::thermo::{self, ThermoData, UserData};
+
+const USAGE: &str = "usage:
+    stoic                                       start the GUI
+    stoic balance <equation> [--integers | --per <formula>] [--decimal]
+    stoic export <latex|markdown|html> <equation> [amount...]
+    stoic equilibrium <equation> <K> <initial...>
+    stoic k <equation> <equilibrium...>
+    stoic thermo <equation> [species=dHf,S,dGf...] [amount...]
+    stoic hess <target> <reaction:dH>...
+    stoic composition <formula>
+    stoic empirical <element=amount>... [--molar-mass <g/mol>]
+    stoic combustion <sample> <CO2> <H2O> [--n2 <g>] [--so2 <g>] [--molar-mass <g/mol>]
+    stoic oxidation <formula|equation>
+    stoic classify <equation>
+    stoic ionic <equation>
+    stoic check <equation>
+    stoic mass <formula> [charge...]
+
+export amounts are given per reactant in order, e.g. 4g, 0.5mol or excess;
+equilibrium amounts are concentrations or partial pressures, one per species;
+thermo data missing from the built-in table is given in kJ/mol, J/(mol K) and kJ/mol;
+--per scales the coefficients so that species has 1 and --decimal writes them as decimals;
+equations written with balanced coefficients (2H2, 1/2 O2) keep them, others are balanced
+to their smallest whole numbers; hess dH is in kJ for the equation in that form;
+empirical amounts are mass percents or grams; combustion masses are in grams;
+mass charges (1, 2+, -1) add or remove protons, e.g. [M+H]+, and default to the formula's own;
+oxidation states marked ? are a best guess and (avg) an average over several atoms;
+any formula can be given as a name instead, e.g. \"iron(III) oxide + carbon monoxide\"";
+
+/// Runs a command given on the command line and returns what should be printed.
+pub(crate) fn run(args: &[String]) -> Result<String, String> {
+    match args.first().map(String::as_str) {
+        Some("balance") => {
+            let input = args.get(1).ok_or(USAGE)?;
+            let mut scale = Scale::AsWritten;
+            let mut decimal = false;
+            let mut iter = args[2..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--integers" => scale = Scale::Integers,
+                    "--per" => {
+                        let species = parse_compound(iter.next().ok_or(USAGE)?)?;
+                        let eq = Balancer::balance(input)?;
+                        let i = eq
+                            .terms
+                            .iter()
+                            .position(|cpd| {
+                                cpd.elements == species.elements && cpd.charge == species.charge
+                            })
+                            .ok_or_else(|| format!("{} is not in the equation", species.raw()))?;
+                        scale = Scale::PerSpecies(i);
+                    }
+                    "--decimal" => decimal = true,
+                    _ => return Err(USAGE.to_string()),
+                }
+            }
+            let eq = Balancer::balance_with(input, scale)?;
+            if decimal {
+                return Ok(format!("{}\n{}", eq.decimal(false), notes(&eq.terms)));
+            }
+            Ok(format!("{}\n{}", eq, notes(&eq.terms)))
+        }
+        Some("export") => {
+            let format = args
+                .get(1)
+                .and_then(|name| Format::from_name(name))
+                .ok_or(USAGE)?;
+            let eq = Balancer::balance(args.get(2).ok_or(USAGE)?)?;
+            let amounts = &args[3..];
+            if amounts.is_empty() {
+                return Ok(export::export(format, &eq, None));
+            }
+            let calculator = stoich(&eq, amounts)?;
+            Ok(export::export(format, &eq, Some(&calculator)))
+        }
+        Some("equilibrium") => {
+            let eq = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let k = parse_number(args.get(2).ok_or(USAGE)?)?;
+            let initial = args[3..]
+                .iter()
+                .map(|amount| parse_number(amount))
+                .collect::<Result<Vec<_>, _>>()?;
+            let table = equilibrium::solve(&eq, &initial, k)?;
+            Ok(format!("{}\n{}", eq, table))
+        }
+        Some("k") => {
+            let eq = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let amounts = args[2..]
+                .iter()
+                .map(|amount| parse_number(amount))
+                .collect::<Result<Vec<_>, _>>()?;
+            let k = equilibrium::equilibrium_constant(&eq, &amounts)?;
+            Ok(format!("{}\nK = {:.6e}\n", eq, k))
+        }
+        Some("thermo") => {
+            let eq = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let (data, amounts): (Vec<_>, Vec<_>) =
+                args[2..].iter().partition(|arg| arg.contains('='));
+            let user = data
+                .into_iter()
+                .map(|arg| parse_thermo_data(arg))
+                .collect::<Result<UserData, _>>()?;
+            let reaction = thermo::reaction(&eq, &user)?;
+            let mut output = format!("{}\n{}", eq, reaction);
+            if !amounts.is_empty() {
+                let amounts = amounts.into_iter().cloned().collect::<Vec<_>>();
+                if let Some(heat) = reaction.heat(&stoich(&eq, &amounts)?) {
+                    let direction = if heat < 0.0 { "released" } else { "absorbed" };
+                    output.push_str(&format!("q = {:.2} kJ ({})\n", heat, direction));
+                }
+            }
+            Ok(output)
+        }
+        Some("hess") => {
+            let target = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let reactions = args[2..]
+                .iter()
+                .map(|arg| {
+                    let (eq, delta_h) = arg
+                        .rsplit_once(':')
+                        .ok_or_else(|| format!("expected <reaction>:<dH>, got '{}'", arg))?;
+                    Ok((Balancer::balance(eq)?, parse_number(delta_h.trim())?))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(format!("{}\n{}", target, hess::solve(&target, &reactions)?))
+        }
+        Some("composition") => {
+            let cpd = parse_compound(args.get(1).ok_or(USAGE)?)?;
+            let mut output = format!("{} ({:.4} g/mol)\n", cpd.raw(), cpd.molar_mass);
+            output.push_str(&notes(std::slice::from_ref(&cpd)));
+            if let Some(name) = names::name(&cpd) {
+                output.push_str(&format!("{}\n", name));
+            }
+            for share in composition::percent_composition(&cpd) {
+                output.push_str(&format!(
+                    "{:<4}{:>6}{:>12.4} g/mol{:>9.2}%\n",
+                    share.element,
+                    parser::decimal(share.count),
+                    share.mass,
+                    share.percent
+                ));
+            }
+            Ok(output)
+        }
+        Some("empirical") => {
+            let mut amounts = Vec::new();
+            let mut molar_mass = None;
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                if arg == "--molar-mass" {
+                    molar_mass = Some(parse_number(iter.next().ok_or(USAGE)?)?);
+                } else {
+                    let (element, amount) = arg
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected <element>=<amount>, got '{}'", arg))?;
+                    amounts.push((element.trim().to_string(), parse_number(amount.trim())?));
+                }
+            }
+            let empirical = composition::empirical_formula(&amounts, DEFAULT_TOLERANCE)?;
+            let mut output = format!(
+                "empirical formula: {} ({:.2} g/mol)\n",
+                empirical, empirical.molar_mass
+            );
+            if let Some(molar_mass) = molar_mass {
+                let molecular =
+                    composition::molecular_formula(&empirical, molar_mass, DEFAULT_TOLERANCE)?;
+                output.push_str(&format!(
+                    "molecular formula: {} ({:.2} g/mol)\n",
+                    molecular, molecular.molar_mass
+                ));
+            }
+            Ok(output)
+        }
+        Some("combustion") => {
+            let mut data = CombustionData::default();
+            let mut molar_mass = None;
+            let mut masses = Vec::new();
+            let mut iter = args[1..].iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--n2" => data.n2 = Some(parse_number(iter.next().ok_or(USAGE)?)?),
+                    "--so2" => data.so2 = Some(parse_number(iter.next().ok_or(USAGE)?)?),
+                    "--molar-mass" => molar_mass = Some(parse_number(iter.next().ok_or(USAGE)?)?),
+                    mass => masses.push(parse_number(mass)?),
+                }
+            }
+            match masses[..] {
+                [sample, co2, h2o] => {
+                    data.sample = sample;
+                    data.co2 = co2;
+                    data.h2o = h2o;
+                }
+                _ => return Err(USAGE.to_string()),
+            }
+            let result = combustion::analyse(&data, DEFAULT_TOLERANCE)?;
+            let mut output = String::new();
+            for (element, mass) in &result.masses {
+                output.push_str(&format!("{}: {:.4} g\n", element, mass));
+            }
+            output.push_str(&format!("empirical formula: {}\n", result.empirical));
+            let mut formula = result.empirical;
+            if let Some(molar_mass) = molar_mass {
+                formula = composition::molecular_formula(&formula, molar_mass, DEFAULT_TOLERANCE)?;
+                output.push_str(&format!("molecular formula: {}\n", formula));
+            }
+            output.push_str(&format!("{}\n", combustion::combustion_equation(&formula)?));
+            Ok(output)
+        }
+        Some("oxidation") => {
+            let input = args.get(1).ok_or(USAGE)?;
+            if !input.contains(['=', '>', '→', '⇌']) {
+                let cpd = parse_compound(input)?;
+                let mut output = format!("{}\n", cpd.raw());
+                for state in redox::oxidation_states(&cpd) {
+                    let mark = match (state.assumed, state.average) {
+                        (true, _) => " ?",
+                        (false, true) => " (avg)",
+                        (false, false) => "",
+                    };
+                    output.push_str(&format!(
+                        "{:<4}{:>6}{}\n",
+                        state.element,
+                        state.display_state(),
+                        mark
+                    ));
+                }
+                return Ok(output);
+            }
+            let eq = Balancer::balance(input)?;
+            let changes = redox::redox_changes(&eq);
+            let mut output = format!("{}\n", eq);
+            if changes.is_empty() {
+                output.push_str("no oxidation states change\n");
+            }
+            for change in changes {
+                output.push_str(&format!(
+                    "{} {}: {} {} → {} in {}\n",
+                    if change.is_oxidation() {
+                        "oxidised"
+                    } else {
+                        "reduced"
+                    },
+                    eq.terms[change.reactant].raw(),
+                    change.element,
+                    redox::signed(change.from),
+                    redox::signed(change.to),
+                    eq.terms[change.product].raw()
+                ));
+            }
+            Ok(output)
+        }
+        Some("classify") => {
+            let eq = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let types = classify::classify(&eq);
+            if types.is_empty() {
+                return Ok(format!("{}\nnot a common reaction type\n", eq));
+            }
+            let types = types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+            Ok(format!("{}\n{}\n", eq, types.join(", ")))
+        }
+        Some("ionic") => {
+            let eq = Balancer::balance(args.get(1).ok_or(USAGE)?)?;
+            let ionic = ionic::ionic_equations(&eq)?;
+            let mut output = format!(
+                "{}\ncomplete ionic: {}\nnet ionic: {}\n",
+                eq, ionic.complete, ionic.net
+            );
+            if !ionic.spectators.is_empty() {
+                output.push_str(&format!("spectators: {}\n", ionic.spectators.join(", ")));
+            }
+            if !ionic.precipitates.is_empty() {
+                output.push_str(&format!(
+                    "precipitates: {}\n",
+                    ionic.precipitates.join(", ")
+                ));
+            }
+            Ok(output)
+        }
+        Some("check") => {
+            let input = args.get(1).ok_or(USAGE)?;
+            let eq = parser::parse(input)?;
+            let imbalances = balance::check(&eq)?;
+            if imbalances.is_empty() {
+                return Ok(format!("{}\n{}balanced\n", eq, notes(&eq.terms)));
+            }
+            let mut output = format!("{}\n{}not balanced\n", eq, notes(&eq.terms));
+            for imbalance in imbalances {
+                output.push_str(&format!("  {}\n", imbalance));
+            }
+            if let Ok(balanced) = Balancer::balance(input) {
+                output.push_str(&format!("balanced: {}\n", balanced));
+            }
+            Ok(output)
+        }
+        Some("mass") => {
+            let cpd = parse_compound(args.get(1).ok_or(USAGE)?)?;
+            let mut output = format!(
+                "{}\n{}monoisotopic mass: {:.5} u\naverage mass: {:.5} u\n",
+                cpd.raw(),
+                notes(std::slice::from_ref(&cpd)),
+                mass_spec::monoisotopic_mass(&cpd)?,
+                mass_spec::average_mass(&cpd)?
+            );
+            let mut charges = args[2..]
+                .iter()
+                .map(|charge| mass_spec::parse_charge(charge))
+                .collect::<Result<Vec<_>, _>>()?;
+            if charges.is_empty() {
+                charges.push(cpd.charge);
+            }
+            for charge in charges {
+                match charge {
+                    0 => output.push_str("neutral\n"),
+                    _ => output.push_str(&format!("z = {:+}\n", charge)),
+                }
+                for peak in mass_spec::isotope_pattern(&cpd, charge)? {
+                    output.push_str(&format!("{:>14.5}{:>9.2}%\n", peak.mz, peak.abundance));
+                }
+            }
+            Ok(output)
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// How any ambiguous symbols in `terms` were read, one line each.
+fn notes(terms: &[Compound]) -> String {
+    terms
+        .iter()
+        .flat_map(|cpd| &cpd.notes)
+        .map(|note| format!("note: {}\n", note))
+        .collect()
+}
+
+fn parse_thermo_data(arg: &str) -> Result<(String, ThermoData), String> {
+    let invalid = || format!("invalid thermodynamic data '{}'", arg);
+    let (species, values) = arg.split_once('=').ok_or_else(invalid)?;
+    let values = values
+        .split(',')
+        .map(|value| value.trim().parse::<f64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    match values[..] {
+        [enthalpy, entropy, gibbs] => Ok((
+            species.to_string(),
+            ThermoData {
+                enthalpy,
+                entropy,
+                gibbs,
+            },
+        )),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_number(number: &str) -> Result<f64, String> {
+    number
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", number))
+}
+
+fn stoich(eq: &ChemicalEquation, amounts: &[String]) -> Result<StoichCalculator, String> {
+    if amounts.len() != eq.rhs_ix {
+        return Err(format!(
+            "expected {} reactant amounts, got {}",
+            eq.rhs_ix,
+            amounts.len()
+        ));
+    }
+    let mut inputs = amounts
+        .iter()
+        .map(|amount| parse_amount(amount))
+        .collect::<Result<Vec<_>, _>>()?;
+    inputs.resize(eq.terms.len(), Reactant::None);
+    let mut calculator = StoichCalculator::new(eq.clone(), inputs);
+    calculator.product_unknown();
+    Ok(calculator)
+}
+
+fn parse_amount(amount: &str) -> Result<Reactant, String> {
+    let invalid = || format!("invalid amount '{}'", amount);
+    if amount == "excess" {
+        Ok(Reactant::Excess)
+    } else if let Some(moles) = amount.strip_suffix("mol") {
+        Ok(Reactant::Moles(
+            moles.trim().parse().map_err(|_| invalid())?,
+        ))
+    } else if let Some(grams) = amount.strip_suffix('g') {
+        Ok(Reactant::Grams(
+            grams.trim().parse().map_err(|_| invalid())?,
+        ))
+    } else {
+        Err(invalid())
+    }
+}
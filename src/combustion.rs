@@ -0,0 +1,116 @@
+use crate::balance::Balancer;
+use crate::composition;
+use crate::parser::{parse_compound, ChemicalEquation, Compound};
+use crate::stoichiometry::str_to_molar_mass;
+use num::ToPrimitive;
+
+/// Oxygen found by difference below this fraction of the sample mass is put down to
+/// experimental error rather than reported as part of the compound.
+const OXYGEN_THRESHOLD: f64 = 0.005;
+
+/// Masses in grams of a sample of a CHNOS compound and of the products of burning it. All the
+/// nitrogen is assumed to be collected as N2 and all the sulfur as SO2.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CombustionData {
+    pub(crate) sample: f64,
+    pub(crate) co2: f64,
+    pub(crate) h2o: f64,
+    pub(crate) n2: Option<f64>,
+    pub(crate) so2: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CombustionResult {
+    /// Mass in grams of each element in the sample, oxygen by difference.
+    pub(crate) masses: Vec<(String, f64)>,
+    pub(crate) empirical: Compound,
+}
+
+/// Mass of `element` in `grams` of `product`.
+fn element_mass(element: &str, product: &str, grams: f64) -> f64 {
+    let product = parse_compound(product).unwrap();
+    let count = product.elements[element].to_f64().unwrap();
+    grams * count * str_to_molar_mass(element) as f64 / product.molar_mass as f64
+}
+
+pub(crate) fn analyse(data: &CombustionData, tolerance: f64) -> Result<CombustionResult, String> {
+    if data.sample <= 0.0 {
+        return Err("sample mass must be positive".to_string());
+    }
+    let mut masses = vec![
+        ("C".to_string(), element_mass("C", "CO2", data.co2)),
+        ("H".to_string(), element_mass("H", "H2O", data.h2o)),
+    ];
+    if let Some(n2) = data.n2 {
+        masses.push(("N".to_string(), n2));
+    }
+    if let Some(so2) = data.so2 {
+        masses.push(("S".to_string(), element_mass("S", "SO2", so2)));
+    }
+    let oxygen = data.sample - masses.iter().map(|(_, m)| m).sum::<f64>();
+    if oxygen < -OXYGEN_THRESHOLD * data.sample {
+        return Err(format!(
+            "the products contain {:.4} g more than the sample",
+            -oxygen
+        ));
+    }
+    if oxygen > OXYGEN_THRESHOLD * data.sample {
+        masses.push(("O".to_string(), oxygen));
+    }
+    masses.retain(|(_, m)| *m > 0.0);
+    let empirical = composition::empirical_formula(&masses, tolerance)?;
+    Ok(CombustionResult { masses, empirical })
+}
+
+/// The products of burning `cpd` completely in oxygen: CO2, H2O, N2 and SO2 for whichever of
+/// C, H, N and S it contains.
+pub(crate) fn combustion_products(cpd: &Compound) -> Vec<&'static str> {
+    let mut products = Vec::new();
+    for (element, product) in [("C", "CO2"), ("H", "H2O"), ("N", "N2"), ("S", "SO2")] {
+        if cpd.elements.contains_key(element) {
+            products.push(product);
+        }
+    }
+    products
+}
+
+/// Balances the complete combustion of `cpd` in oxygen to CO2, H2O, N2 and SO2.
+pub(crate) fn combustion_equation(cpd: &Compound) -> Result<ChemicalEquation, String> {
+    let products = combustion_products(cpd);
+    if products.is_empty() {
+        return Err(format!("{} has nothing to burn", cpd.raw()));
+    }
+    Balancer::balance(&format!("{} + O2 = {}", cpd.raw(), products.join(" + ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::composition::DEFAULT_TOLERANCE;
+
+    fn ethene() -> Compound {
+        let data = CombustionData {
+            sample: 1.0,
+            co2: 3.138,
+            h2o: 1.2845,
+            ..Default::default()
+        };
+        analyse(&data, DEFAULT_TOLERANCE).unwrap().empirical
+    }
+
+    #[test]
+    fn finds_the_molecular_formula_from_the_molar_mass() {
+        assert_eq!(ethene().to_string(), "CH2");
+        let molecular = composition::molecular_formula(&ethene(), 28.05, DEFAULT_TOLERANCE);
+        assert_eq!(molecular.unwrap().to_string(), "C2H4");
+    }
+
+    #[test]
+    fn rejects_molar_masses_that_are_too_large_or_not_numbers() {
+        for molar_mass in [1e30, f64::NAN] {
+            assert!(
+                composition::molecular_formula(&ethene(), molar_mass, DEFAULT_TOLERANCE).is_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::isotopes;
+use crate::parser::{parse_compound, Compound};
+use crate::stoichiometry::element_molar_mass;
+use num::rational::Ratio;
+use num::ToPrimitive;
+use std::fmt::Write;
+
+/// Default allowance for experimental error when turning mole ratios into whole numbers.
+pub(crate) const DEFAULT_TOLERANCE: f64 = 0.1;
+/// The largest multiplier tried when clearing ratios such as 1.33 or 1.25 to whole numbers.
+const MAX_MULTIPLIER: usize = 12;
+
+/// One element's share of a compound.
+#[derive(Debug, Clone)]
+pub(crate) struct ElementShare {
+    pub(crate) element: String,
+    pub(crate) count: Ratio<usize>,
+    /// Contribution to the molar mass in g/mol.
+    pub(crate) mass: f32,
+    pub(crate) percent: f32,
+}
+
+/// Breaks a compound's molar mass down by element, in the order the elements were written.
+pub(crate) fn percent_composition(cpd: &Compound) -> Vec<ElementShare> {
+    cpd.elements
+        .iter()
+        .map(|(element, count)| {
+            let mass = isotopes::molar_mass(element).unwrap() * count.to_f32().unwrap();
+            ElementShare {
+                element: element.clone(),
+                count: *count,
+                mass,
+                percent: 100.0 * mass / cpd.molar_mass,
+            }
+        })
+        .collect()
+}
+
+/// Finds the empirical formula from the amount of each element, given either as mass percents
+/// or as masses in grams; only their proportions matter.
+///
+/// The mole ratios are scaled by the smallest multiplier that brings every ratio within
+/// `tolerance` of a whole number, so 1 : 1.33 becomes 3 : 4.
+pub(crate) fn empirical_formula(
+    amounts: &[(String, f64)],
+    tolerance: f64,
+) -> Result<Compound, String> {
+    if amounts.is_empty() {
+        return Err("no elements given".to_string());
+    }
+    let mut moles = Vec::with_capacity(amounts.len());
+    for (element, amount) in amounts {
+        let molar_mass = element_molar_mass(element)
+            .ok_or_else(|| format!("'{}' is not an element", element))?;
+        if *amount <= 0.0 {
+            return Err(format!("amount of {} must be positive", element));
+        }
+        moles.push(amount / molar_mass as f64);
+    }
+    let smallest = moles.iter().cloned().fold(f64::INFINITY, f64::min);
+    let ratios = moles.iter().map(|m| m / smallest).collect::<Vec<_>>();
+    let multiplier = (1..=MAX_MULTIPLIER)
+        .find(|k| {
+            ratios.iter().all(|r| {
+                let scaled = r * *k as f64;
+                (scaled - scaled.round()).abs() <= tolerance
+            })
+        })
+        .ok_or_else(|| {
+            format!(
+                "mole ratios {} are not close to whole numbers",
+                ratios
+                    .iter()
+                    .map(|r| format!("{:.3}", r))
+                    .collect::<Vec<_>>()
+                    .join(" : ")
+            )
+        })?;
+    let counts = ratios
+        .iter()
+        .map(|r| (r * multiplier as f64).round() as usize)
+        .collect::<Vec<_>>();
+    parse_compound(&formula(
+        amounts.iter().map(|(e, _)| e.as_str()).zip(counts),
+    ))
+}
+
+/// Scales an empirical formula up to the whole-number multiple closest to `molar_mass`.
+/// Fails if no multiple is within `tolerance` (as a fraction) of the given molar mass.
+pub(crate) fn molecular_formula(
+    empirical: &Compound,
+    molar_mass: f64,
+    tolerance: f64,
+) -> Result<Compound, String> {
+    if !molar_mass.is_finite() || molar_mass <= 0.0 {
+        return Err("the molar mass must be a positive number".to_string());
+    }
+    let empirical_mass = empirical.molar_mass as f64;
+    let n = (molar_mass / empirical_mass).round().max(1.0);
+    if ((n * empirical_mass - molar_mass) / molar_mass).abs() > tolerance {
+        return Err(format!(
+            "{:.2} g/mol is not a whole-number multiple of the empirical formula mass {:.2} g/mol",
+            molar_mass, empirical_mass
+        ));
+    }
+    let counts = empirical
+        .counts()
+        .ok_or("an empirical formula needs whole-number subscripts")?;
+    let too_large = || format!("{} g/mol is too large for a molecular formula", molar_mass);
+    let n = n.to_usize().ok_or_else(too_large)?;
+    let counts = counts
+        .iter()
+        .map(|(elem, count)| Some((elem.as_str(), count.checked_mul(n)?)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(too_large)?;
+    parse_compound(&formula(counts.into_iter()))
+}
+
+fn formula<'a>(counts: impl Iterator<Item = (&'a str, usize)>) -> String {
+    let mut f = String::new();
+    for (elem, count) in counts {
+        match count {
+            1 => write!(f, "{}", elem).unwrap(),
+            _ => write!(f, "{}{}", elem, count).unwrap(),
+        }
+    }
+    f
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn butene() -> Compound {
+        let amounts = [("C".to_string(), 85.6), ("H".to_string(), 14.4)];
+        empirical_formula(&amounts, DEFAULT_TOLERANCE).unwrap()
+    }
+
+    #[test]
+    fn scales_the_empirical_formula_to_the_molar_mass() {
+        assert_eq!(butene().to_string(), "CH2");
+        let molecular = molecular_formula(&butene(), 56.1, DEFAULT_TOLERANCE).unwrap();
+        assert_eq!(molecular.to_string(), "C4H8");
+    }
+
+    #[test]
+    fn rejects_molar_masses_that_are_not_positive_numbers() {
+        for molar_mass in [f64::NAN, f64::INFINITY, 0.0, -56.1] {
+            assert!(molecular_formula(&butene(), molar_mass, DEFAULT_TOLERANCE).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_molecular_formulas_that_overflow() {
+        assert!(molecular_formula(&butene(), 1e30, DEFAULT_TOLERANCE).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::parser::{ChemicalEquation, Phase, Side};
+use num::ToPrimitive;
+use std::fmt::{Display, Formatter};
+
+const MAX_ITERATIONS: usize = 400;
+
+/// One row of an ICE (initial, change, equilibrium) table. Amounts are concentrations or
+/// partial pressures, whichever the inputs were given in.
+#[derive(Debug, Clone)]
+pub(crate) struct IceRow {
+    pub(crate) species: String,
+    pub(crate) initial: f64,
+    pub(crate) change: f64,
+    pub(crate) equilibrium: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct IceTable {
+    pub(crate) rows: Vec<IceRow>,
+    /// How far the reaction proceeded, in units of the equation as balanced. Negative when
+    /// the reaction ran in reverse.
+    pub(crate) extent: f64,
+}
+
+/// Signed stoichiometric numbers of the species that appear in the equilibrium expression.
+/// Pure solids and liquids have unit activity, so they are left out.
+fn participants(eq: &ChemicalEquation) -> Vec<(usize, f64)> {
+    eq.terms
+        .iter()
+        .enumerate()
+        .filter(|(_, cpd)| !matches!(cpd.phase, Some(Phase::Solid | Phase::Liquid)))
+        .map(|(i, cpd)| {
+            let nu = cpd.coefficient.to_f64().unwrap();
+            match cpd.side {
+                Side::LHS => (i, -nu),
+                Side::RHS => (i, nu),
+            }
+        })
+        .collect()
+}
+
+/// Computes K from equilibrium amounts given for every term of `eq`.
+pub(crate) fn equilibrium_constant(eq: &ChemicalEquation, amounts: &[f64]) -> Result<f64, String> {
+    if amounts.len() != eq.terms.len() {
+        return Err(format!(
+            "expected {} amounts, got {}",
+            eq.terms.len(),
+            amounts.len()
+        ));
+    }
+    let mut ln_k = 0.0;
+    for (i, nu) in participants(eq) {
+        if amounts[i] <= 0.0 {
+            return Err(format!(
+                "equilibrium amount of {} must be positive",
+                eq.terms[i].raw()
+            ));
+        }
+        ln_k += nu * amounts[i].ln();
+    }
+    Ok(ln_k.exp())
+}
+
+/// Solves for the equilibrium position from initial amounts given for every term of `eq`.
+///
+/// The full mass-action expression is solved, with no small-x approximation. Written as
+/// `sum(nu * ln(c0 + nu * x)) = ln K` it is strictly increasing in the extent `x` over the range
+/// where every amount stays positive, so it has exactly one physical root, which is found by
+/// bisection.
+pub(crate) fn solve(eq: &ChemicalEquation, initial: &[f64], k: f64) -> Result<IceTable, String> {
+    if initial.len() != eq.terms.len() {
+        return Err(format!(
+            "expected {} initial amounts, got {}",
+            eq.terms.len(),
+            initial.len()
+        ));
+    }
+    if k <= 0.0 || !k.is_finite() {
+        return Err("K must be a positive number".to_string());
+    }
+    if initial.iter().any(|c| *c < 0.0) {
+        return Err("initial amounts cannot be negative".to_string());
+    }
+    let species = participants(eq);
+    if species.is_empty() {
+        return Err("no species appear in the equilibrium expression".to_string());
+    }
+    // The extent is bounded by the reactants running out going forward and the products
+    // running out going in reverse.
+    let mut lo = f64::NEG_INFINITY;
+    let mut hi = f64::INFINITY;
+    for (i, nu) in &species {
+        let bound = -initial[*i] / nu;
+        if *nu < 0.0 {
+            hi = hi.min(bound);
+        } else {
+            lo = lo.max(bound);
+        }
+    }
+    if lo >= hi {
+        return Err("no amount of reaction keeps every species positive".to_string());
+    }
+    let ln_k = k.ln();
+    let f = |x: f64| {
+        species
+            .iter()
+            .map(|(i, nu)| nu * (initial[*i] + nu * x).ln())
+            .sum::<f64>()
+            - ln_k
+    };
+    if lo.is_infinite() {
+        lo = hi.min(0.0) - 1.0;
+        while f(lo) > 0.0 {
+            lo = 2.0 * lo - hi.min(0.0);
+        }
+    }
+    if hi.is_infinite() {
+        hi = lo.max(0.0) + 1.0;
+        while f(hi) < 0.0 {
+            hi = 2.0 * hi - lo.max(0.0);
+        }
+    }
+    for _ in 0..MAX_ITERATIONS {
+        let mid = lo + (hi - lo) / 2.0;
+        if mid <= lo || mid >= hi {
+            break;
+        }
+        if f(mid) < 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let extent = lo + (hi - lo) / 2.0;
+    let rows = species
+        .iter()
+        .map(|(i, nu)| IceRow {
+            species: eq.terms[*i].raw(),
+            initial: initial[*i],
+            change: nu * extent,
+            equilibrium: (initial[*i] + nu * extent).max(0.0),
+        })
+        .collect();
+    Ok(IceTable { rows, extent })
+}
+
+impl Display for IceTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<16}{:>14}{:>14}{:>14}",
+            "Species", "Initial", "Change", "Equilibrium"
+        )?;
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{:<16}{:>14.6e}{:>+14.6e}{:>14.6e}",
+                row.species, row.initial, row.change, row.equilibrium
+            )?;
+        }
+        writeln!(f, "extent of reaction: {:.6e}", self.extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn haber() -> ChemicalEquation {
+        parser::parse("N2 + 3H2 <=> 2NH3").unwrap()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn solves_haber_process_forward() {
+        let table = solve(&haber(), &[1.0, 3.0, 0.0], 0.5).unwrap();
+        assert_close(table.extent, 0.4857781364196438);
+        let amounts = table.rows.iter().map(|row| row.equilibrium);
+        for (amount, expected) in
+            amounts.zip([0.5142218635803562, 1.5426655907410687, 0.9715562728392876])
+        {
+            assert_close(amount, expected);
+        }
+    }
+
+    #[test]
+    fn solves_haber_process_in_reverse() {
+        let table = solve(&haber(), &[0.0, 0.0, 2.0], 0.5).unwrap();
+        assert_close(table.extent, 0.4857781364196438 - 1.0);
+        assert_close(table.rows[2].equilibrium, 0.9715562728392876);
+    }
+
+    #[test]
+    fn equilibrium_amounts_give_back_k() {
+        let table = solve(&haber(), &[1.0, 3.0, 0.0], 0.5).unwrap();
+        let amounts = table
+            .rows
+            .iter()
+            .map(|row| row.equilibrium)
+            .collect::<Vec<_>>();
+        assert_close(equilibrium_constant(&haber(), &amounts).unwrap(), 0.5);
+    }
+
+    #[test]
+    fn rejects_a_non_positive_k() {
+        assert!(solve(&haber(), &[1.0, 3.0, 0.0], 0.0).is_err());
+    }
+}
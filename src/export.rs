@@ -0,0 +1,244 @@
+use crate::parser::{Arrow, ChemicalEquation, Compound, Segment};
+use crate::stoichiometry::{SpeciesResult, StoichCalculator};
+use num::rational::Ratio;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Latex,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub(crate) const ALL: [Format; 3] = [Format::Latex, Format::Markdown, Format::Html];
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "latex" | "tex" => Some(Format::Latex),
+            "markdown" | "md" => Some(Format::Markdown),
+            "html" => Some(Format::Html),
+            _ => None,
+        }
+    }
+    pub(crate) fn extension(&self) -> &'static str {
+        match self {
+            Format::Latex => "tex",
+            Format::Markdown => "md",
+            Format::Html => "html",
+        }
+    }
+}
+
+const HEADERS: [&str; 8] = [
+    "Species",
+    "Role",
+    "Initial (mol)",
+    "Change (mol)",
+    "Final (mol)",
+    "Initial (g)",
+    "Change (g)",
+    "Final (g)",
+];
+
+/// Writes a balanced equation and, if a stoichiometry run is given, its results table.
+pub(crate) fn export(
+    format: Format,
+    eq: &ChemicalEquation,
+    calculator: Option<&StoichCalculator>,
+) -> String {
+    let rows = calculator
+        .filter(|calc| calc.results.len() == calc.eq.terms.len())
+        .map(|calc| calc.eq.terms.iter().zip(&calc.results).collect::<Vec<_>>())
+        .unwrap_or_default();
+    match format {
+        Format::Latex => latex(eq, &rows),
+        Format::Markdown => markdown(eq, &rows),
+        Format::Html => html(eq, &rows),
+    }
+}
+
+/// The results table as tab-separated values, which paste cleanly into spreadsheets.
+pub(crate) fn results_tsv(calculator: &StoichCalculator) -> String {
+    let mut f = String::new();
+    writeln!(f, "{}", HEADERS.join("\t")).unwrap();
+    for result in &calculator.results {
+        writeln!(f, "{}\t{}", result.species, cells(result).join("\t")).unwrap();
+    }
+    f
+}
+
+fn equation_with(
+    eq: &ChemicalEquation,
+    arrow: &str,
+    cpd_fmt: impl Fn(&Compound) -> String,
+) -> String {
+    let side = |terms: &[Compound]| terms.iter().map(&cpd_fmt).collect::<Vec<_>>().join(" + ");
+    format!(
+        "{} {} {}",
+        side(&eq.terms[0..eq.rhs_ix]),
+        arrow,
+        side(&eq.terms[eq.rhs_ix..eq.terms.len()])
+    )
+}
+
+/// A compound in mhchem syntax, which is the plain syntax except for isotopes (^{13}C).
+fn mhchem(cpd: &Compound, with_coefficient: bool) -> String {
+    let mut f = String::new();
+    for segment in cpd.segments() {
+        match segment {
+            Segment::Coefficient(s) if with_coefficient => f.push_str(&s),
+            Segment::Coefficient(_) => (),
+            Segment::Symbol(s) | Segment::Subscript(s) | Segment::Multiplier(s) => f.push_str(&s),
+            Segment::Isotope(a, s) => write!(f, "^{{{}}}{}", a, s).unwrap(),
+            Segment::Charge(s) => write!(f, "^{}", s).unwrap(),
+            Segment::Dot => f.push('*'),
+            Segment::Phase(p) => write!(f, "({})", p).unwrap(),
+        }
+    }
+    f
+}
+
+fn mhchem_arrow(arrow: Arrow) -> &'static str {
+    match arrow {
+        Arrow::Equals | Arrow::Forward => "->",
+        Arrow::Reversible => "<=>",
+    }
+}
+
+fn html_arrow(arrow: Arrow) -> &'static str {
+    match arrow {
+        Arrow::Equals => "=",
+        Arrow::Forward => "&rarr;",
+        Arrow::Reversible => "&#8652;",
+    }
+}
+
+fn cells(result: &SpeciesResult) -> [String; 7] {
+    let amount = |amount: Option<f32>| match amount {
+        Some(amount) => format!("{:.4}", amount),
+        None => "excess".to_string(),
+    };
+    [
+        format!("{:?}", result.role),
+        amount(result.initial),
+        format!("{:+.4}", result.change),
+        amount(result.final_amount),
+        amount(result.initial.map(|m| result.grams(m))),
+        format!("{:+.4}", result.grams(result.change)),
+        amount(result.final_amount.map(|m| result.grams(m))),
+    ]
+}
+
+fn latex(eq: &ChemicalEquation, rows: &[(&Compound, &SpeciesResult)]) -> String {
+    let mut f = String::new();
+    writeln!(
+        f,
+        "\\ce{{{}}}",
+        equation_with(eq, mhchem_arrow(eq.arrow), |cpd| mhchem(cpd, true))
+    )
+    .unwrap();
+    if rows.is_empty() {
+        return f;
+    }
+    writeln!(f).unwrap();
+    writeln!(f, "\\begin{{tabular}}{{llrrrrrr}}").unwrap();
+    writeln!(f, "\\hline").unwrap();
+    writeln!(f, "{} \\\\", HEADERS.join(" & ")).unwrap();
+    writeln!(f, "\\hline").unwrap();
+    for (cpd, result) in rows {
+        writeln!(
+            f,
+            "\\ce{{{}}} & {} \\\\",
+            mhchem(cpd, false),
+            cells(result).join(" & ")
+        )
+        .unwrap();
+    }
+    writeln!(f, "\\hline").unwrap();
+    writeln!(f, "\\end{{tabular}}").unwrap();
+    f
+}
+
+fn markdown(eq: &ChemicalEquation, rows: &[(&Compound, &SpeciesResult)]) -> String {
+    let mut f = String::new();
+    writeln!(f, "{}", eq.unicode()).unwrap();
+    if rows.is_empty() {
+        return f;
+    }
+    writeln!(f).unwrap();
+    writeln!(f, "| {} |", HEADERS.join(" | ")).unwrap();
+    writeln!(f, "|---|---|{}", "---:|".repeat(HEADERS.len() - 2)).unwrap();
+    for (cpd, result) in rows {
+        let mut cpd = (*cpd).clone();
+        cpd.coefficient = Ratio::from_integer(1);
+        writeln!(f, "| {} | {} |", cpd.unicode(), cells(result).join(" | ")).unwrap();
+    }
+    f
+}
+
+fn html_compound(cpd: &Compound, with_coefficient: bool) -> String {
+    let mut f = String::new();
+    for segment in cpd.segments() {
+        match segment {
+            Segment::Coefficient(s) if with_coefficient => f.push_str(&s),
+            Segment::Coefficient(_) => (),
+            Segment::Symbol(s) | Segment::Multiplier(s) => f.push_str(&s),
+            Segment::Subscript(s) => write!(f, "<sub>{}</sub>", s).unwrap(),
+            Segment::Charge(s) => write!(f, "<sup>{}</sup>", s.replace('-', "&minus;")).unwrap(),
+            Segment::Isotope(a, s) => write!(f, "<sup>{}</sup>{}", a, s).unwrap(),
+            Segment::Dot => f.push_str("&middot;"),
+            Segment::Phase(p) => write!(f, "({})", p).unwrap(),
+        }
+    }
+    f
+}
+
+fn html(eq: &ChemicalEquation, rows: &[(&Compound, &SpeciesResult)]) -> String {
+    let mut f = String::new();
+    writeln!(f, "<!DOCTYPE html>").unwrap();
+    writeln!(f, "<html>").unwrap();
+    writeln!(f, "<head>").unwrap();
+    writeln!(f, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(f, "<title>stoic</title>").unwrap();
+    writeln!(
+        f,
+        "<style>table {{ border-collapse: collapse; }} th, td {{ border: 1px solid #999; padding: 4px 8px; }} td.num {{ text-align: right; }} tr.limiting {{ font-weight: bold; }}</style>"
+    )
+    .unwrap();
+    writeln!(f, "</head>").unwrap();
+    writeln!(f, "<body>").unwrap();
+    writeln!(
+        f,
+        "<p>{}</p>",
+        equation_with(eq, html_arrow(eq.arrow), |cpd| html_compound(cpd, true))
+    )
+    .unwrap();
+    if !rows.is_empty() {
+        writeln!(f, "<table>").unwrap();
+        write!(f, "<tr>").unwrap();
+        for header in HEADERS {
+            write!(f, "<th>{}</th>", header).unwrap();
+        }
+        writeln!(f, "</tr>").unwrap();
+        for (cpd, result) in rows {
+            let [role, numbers @ ..] = cells(result);
+            write!(
+                f,
+                "<tr class=\"{}\"><td>{}</td><td>{}</td>",
+                role.to_lowercase(),
+                html_compound(cpd, false),
+                role
+            )
+            .unwrap();
+            for number in numbers {
+                write!(f, "<td class=\"num\">{}</td>", number).unwrap();
+            }
+            writeln!(f, "</tr>").unwrap();
+        }
+        writeln!(f, "</table>").unwrap();
+    }
+    writeln!(f, "</body>").unwrap();
+    writeln!(f, "</html>").unwrap();
+    f
+}
@@ -0,0 +1,128 @@
+use crate::matrix::GaussianElimination;
+use crate::parser::{to_signed, ChemicalEquation};
+use indexmap::IndexMap;
+use na::DMatrix;
+use num::rational::Ratio;
+use num::{ToPrimitive, Zero};
+use std::fmt::{Display, Formatter};
+
+/// A known reaction scaled by the multiplier needed to build the target. A negative
+/// multiplier means the reaction is reversed.
+#[derive(Debug, Clone)]
+pub(crate) struct HessStep {
+    pub(crate) multiplier: Ratio<isize>,
+    pub(crate) equation: String,
+    pub(crate) delta_h: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HessSolution {
+    pub(crate) steps: Vec<HessStep>,
+    pub(crate) delta_h: f64,
+}
+
+/// Finds multipliers for `reactions`, each given with its ΔH, that sum to `target`.
+///
+/// Species are matched as written (see `Compound::raw`), so H2O(l) and H2O(g) are different
+/// species. Each species gives one row, like each element does when balancing, and a solution
+/// is a null-space vector of `[reactions | -target]` whose last entry is non-zero.
+pub(crate) fn solve(
+    target: &ChemicalEquation,
+    reactions: &[(ChemicalEquation, f64)],
+) -> Result<HessSolution, String> {
+    let mut species = IndexMap::new();
+    for eq in reactions.iter().map(|(eq, _)| eq).chain([target]) {
+        for cpd in &eq.terms {
+            let row = species.len();
+            species.entry(cpd.raw()).or_insert(row);
+        }
+    }
+    let mut matrix: DMatrix<Ratio<isize>> =
+        DMatrix::from_element(species.len(), reactions.len() + 1, Ratio::zero());
+    let columns = reactions
+        .iter()
+        .map(|(eq, _)| (eq, Ratio::from_integer(1)))
+        .chain([(target, Ratio::from_integer(-1))]);
+    for (col, (eq, sign)) in columns.enumerate() {
+        for cpd in &eq.terms {
+            matrix[(species[&cpd.raw()], col)] +=
+                to_signed(cpd.coefficient) * sign * &cpd.side * -1;
+        }
+    }
+    let no_combination = || "the target cannot be made from these reactions".to_string();
+    let basis = GaussianElimination::new(matrix)
+        .solve()
+        .map_err(|_| no_combination())?;
+    let solution = basis
+        .into_iter()
+        .find(|v| !v[reactions.len()].is_zero())
+        .ok_or_else(no_combination)?;
+    let scale = solution[reactions.len()];
+    let steps = reactions
+        .iter()
+        .zip(&solution)
+        .map(|((eq, delta_h), m)| (eq, delta_h, m / scale))
+        .filter(|(_, _, multiplier)| !multiplier.is_zero())
+        .map(|(eq, delta_h, multiplier)| HessStep {
+            multiplier,
+            equation: eq.to_string(),
+            delta_h: *delta_h,
+        })
+        .collect::<Vec<_>>();
+    let delta_h = steps
+        .iter()
+        .map(|step| step.multiplier.to_f64().unwrap() * step.delta_h)
+        .sum();
+    Ok(HessSolution { steps, delta_h })
+}
+
+impl Display for HessSolution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{:>6} × ({})  ΔH = {:.1} kJ",
+                step.multiplier.to_string(),
+                step.equation,
+                step.delta_h
+            )?;
+        }
+        writeln!(f, "ΔH = {:.1} kJ", self.delta_h)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::Balancer;
+
+    fn formations() -> Vec<(ChemicalEquation, f64)> {
+        [
+            ("C + 2H2 = CH4", -74.6),
+            ("C + O2 = CO2", -393.5),
+            ("H2 + 1/2O2 = H2O", -285.8),
+        ]
+        .into_iter()
+        .map(|(eq, delta_h)| (Balancer::balance(eq).unwrap(), delta_h))
+        .collect()
+    }
+
+    #[test]
+    fn combines_formations_into_methane_combustion() {
+        let target = Balancer::balance("CH4 + 2O2 = CO2 + 2H2O").unwrap();
+        let solution = solve(&target, &formations()).unwrap();
+        let multipliers = solution
+            .steps
+            .iter()
+            .map(|step| step.multiplier)
+            .collect::<Vec<_>>();
+        assert_eq!(multipliers, [-1, 1, 2].map(Ratio::from_integer).to_vec());
+        assert!((solution.delta_h - -890.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_a_target_the_reactions_cannot_make() {
+        let target = Balancer::balance("N2 + 3H2 = 2NH3").unwrap();
+        assert!(solve(&target, &formations()).is_err());
+    }
+}
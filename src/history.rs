@@ -0,0 +1,97 @@
+use crate::session::Session;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const HISTORY_KEY: &str = "history";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum EntryKind {
+    Balanced,
+    StoichRun,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    pub(crate) kind: EntryKind,
+    pub(crate) equation: String,
+    pub(crate) session: Session,
+    pub(crate) pinned: bool,
+}
+
+impl HistoryEntry {
+    pub(crate) fn summary(&self) -> String {
+        match self.kind {
+            EntryKind::Balanced => self.equation.clone(),
+            EntryKind::StoichRun => {
+                let inputs = self
+                    .session
+                    .stoich_input_strings
+                    .iter()
+                    .zip(&self.session.stoich_input_reactants)
+                    .filter(|((amount, _), _)| !amount.is_empty())
+                    .map(|((amount, sufficient), reactant)| {
+                        if *sufficient {
+                            "excess".to_string()
+                        } else {
+                            format!("{} {}", amount, reactant.list_display())
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let outputs = self
+                    .session
+                    .outputs
+                    .iter()
+                    .map(|op| format!("{:.3}", op))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}\n[{}] -> [{}]", self.equation, inputs, outputs)
+            }
+        }
+    }
+    fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || self
+                .summary()
+                .to_lowercase()
+                .contains(&query.to_lowercase())
+    }
+}
+
+/// A record of the equations balanced and stoichiometry runs made in the app, newest last.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct History {
+    pub(crate) entries: Vec<HistoryEntry>,
+    pub(crate) search: String,
+}
+
+impl History {
+    pub(crate) fn record(&mut self, kind: EntryKind, equation: String, session: Session) {
+        if let Some(last) = self.entries.last() {
+            if last.kind == EntryKind::Balanced
+                && kind == EntryKind::Balanced
+                && last.equation == equation
+            {
+                return;
+            }
+        }
+        self.entries.push(HistoryEntry {
+            kind,
+            equation,
+            session,
+            pinned: false,
+        });
+    }
+    /// Indices of the entries matching the current search, pinned entries first and
+    /// otherwise newest first.
+    pub(crate) fn visible(&self) -> Vec<usize> {
+        let mut ixs = (0..self.entries.len())
+            .rev()
+            .filter(|i| self.entries[*i].matches(&self.search))
+            .collect::<Vec<_>>();
+        ixs.sort_by_key(|i| !self.entries[*i].pinned);
+        ixs
+    }
+    pub(crate) fn clear_unpinned(&mut self) {
+        self.entries.retain(|entry| entry.pinned);
+    }
+}
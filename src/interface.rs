@@ -1,49 +1,273 @@
-use crate::balance::Balancer;
+use crate::balance::{self, Balancer, Scale};
+use crate::classify::{self, ReactionType};
+use crate::combustion::{self, CombustionData};
+use crate::composition::{self, ElementShare, DEFAULT_TOLERANCE};
+use crate::equilibrium::{self, IceTable};
+use crate::export::{self, Format};
+use crate::hess::{self, HessSolution};
+use crate::history::{EntryKind, History, HISTORY_KEY};
+use crate::ionic::{self, IonicEquations};
+use crate::mass_spec::{self, Peak};
+use crate::names;
+use crate::parser::{self, Segment};
 use crate::parser::{ChemicalEquation, Compound};
-use crate::stoichiometry::{Reactant, StoichCalculator};
-use eframe::egui::{Context, Ui, Visuals};
-use eframe::{egui, Frame};
+use crate::redox::{self, OxidationState, RedoxChange};
+use crate::session::{Session, DEFAULT_SESSION_PATH};
+use crate::stoichiometry::{Reactant, Role, SpeciesResult, StoichCalculator};
+use crate::thermo::{self, ThermoData, UserData};
+use eframe::egui::text::LayoutJob;
+use eframe::egui::{
+    Align, Align2, Color32, Context, FontId, Pos2, Rect, RichText, Sense, Stroke, TextFormat,
+    TextStyle, Ui, Vec2, Visuals,
+};
+use eframe::{egui, Frame, Storage};
+use num::rational::Ratio;
+use num::One;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, PartialEq)]
-enum StoichMode {
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum StoichMode {
     ProductUnknown,
     ReactantUnknown,
 }
 
+struct CombustionReport {
+    masses: Vec<(String, f64)>,
+    formulas: Vec<Compound>,
+    equation: ChemicalEquation,
+}
+
+struct MassReport {
+    cpd: Compound,
+    monoisotopic: f64,
+    average: f64,
+    peaks: Vec<Peak>,
+}
+
+/// Everything shown about the balanced equation, worked out once each time it changes rather
+/// than on every frame.
+struct Analysis {
+    names: Vec<Option<String>>,
+    compositions: Vec<Vec<(ElementShare, OxidationState)>>,
+    changes: Vec<RedoxChange>,
+    imbalances: Result<Vec<String>, String>,
+    types: Vec<ReactionType>,
+    ionic: Result<IonicEquations, String>,
+}
+
+impl Analysis {
+    fn new(input: &str, eq: &ChemicalEquation) -> Self {
+        Analysis {
+            names: eq.terms.iter().map(names::name).collect(),
+            compositions: eq
+                .terms
+                .iter()
+                .map(|cpd| {
+                    composition::percent_composition(cpd)
+                        .into_iter()
+                        .zip(redox::oxidation_states(cpd))
+                        .collect()
+                })
+                .collect(),
+            changes: redox::redox_changes(eq),
+            imbalances: written_imbalances(input),
+            types: classify::classify(eq),
+            ionic: ionic::ionic_equations(eq),
+        }
+    }
+}
+
 pub(crate) struct App {
     eq_input: String,
     eq_display: ChemicalEquation,
+    analysed: Option<(String, Scale)>,
+    analysis: Analysis,
     selected_stoich_mode: StoichMode,
     stoich_input_strings: Vec<(String, bool)>,
     stoich_input_reactants: Vec<Reactant>,
     stoich_calculator: StoichCalculator,
+    session_path: String,
+    session_status: String,
+    history: History,
+    unicode_formulas: bool,
+    coefficient_scale: Scale,
+    decimal_coefficients: bool,
+    export_format: Format,
+    export_output: String,
+    equilibrium_inputs: Vec<String>,
+    k_input: String,
+    equilibrium_output: Result<Option<IceTable>, String>,
+    k_output: String,
+    thermo_user: UserData,
+    thermo_inputs: HashMap<String, [String; 3]>,
+    temperature_input: String,
+    hess_inputs: Vec<(String, String)>,
+    hess_output: Result<Option<HessSolution>, String>,
+    empirical_inputs: Vec<(String, String)>,
+    empirical_molar_mass: String,
+    empirical_output: Result<Vec<(&'static str, Compound)>, String>,
+    combustion_inputs: [String; 6],
+    combustion_output: Result<Option<CombustionReport>, String>,
+    mass_input: String,
+    charge_input: String,
+    mass_analysed: Option<(String, String)>,
+    mass_output: Result<Option<MassReport>, String>,
 }
 
 impl App {
     pub(crate) fn new(cc: &eframe::CreationContext<'_>) -> Self {
         cc.egui_ctx.set_visuals(Visuals::dark());
-        App {
+        let mut app = App {
             eq_input: String::new(),
             eq_display: ChemicalEquation::empty(),
+            analysed: None,
+            analysis: Analysis::new("", &ChemicalEquation::empty()),
             selected_stoich_mode: StoichMode::ProductUnknown,
             stoich_input_strings: Vec::new(),
             stoich_input_reactants: Vec::new(),
             stoich_calculator: StoichCalculator::new(ChemicalEquation::empty(), Vec::new()),
+            session_path: DEFAULT_SESSION_PATH.to_string(),
+            session_status: String::new(),
+            history: History::default(),
+            unicode_formulas: false,
+            coefficient_scale: Scale::AsWritten,
+            decimal_coefficients: false,
+            export_format: Format::Latex,
+            export_output: String::new(),
+            equilibrium_inputs: Vec::new(),
+            k_input: String::new(),
+            equilibrium_output: Ok(None),
+            k_output: String::new(),
+            thermo_user: UserData::new(),
+            thermo_inputs: HashMap::new(),
+            temperature_input: "298.15".to_string(),
+            hess_inputs: vec![(String::new(), String::new())],
+            hess_output: Ok(None),
+            empirical_inputs: vec![(String::new(), String::new())],
+            empirical_molar_mass: String::new(),
+            empirical_output: Ok(Vec::new()),
+            combustion_inputs: Default::default(),
+            combustion_output: Ok(None),
+            mass_input: String::new(),
+            charge_input: String::new(),
+            mass_analysed: None,
+            mass_output: Ok(None),
+        };
+        if let Some(storage) = cc.storage {
+            if let Some(session) = eframe::get_value::<Session>(storage, eframe::APP_KEY) {
+                app.restore(session);
+            }
+            if let Some(history) = eframe::get_value::<History>(storage, HISTORY_KEY) {
+                app.history = history;
+            }
+        }
+        app
+    }
+    pub(crate) fn session(&self) -> Session {
+        Session {
+            eq_input: self.eq_input.clone(),
+            stoich_mode: self.selected_stoich_mode.clone(),
+            stoich_input_strings: self.stoich_input_strings.clone(),
+            stoich_input_reactants: self.stoich_input_reactants.clone(),
+            outputs: self.stoich_calculator.outputs.clone(),
+            results: self.stoich_calculator.results.clone(),
         }
     }
+    pub(crate) fn restore(&mut self, session: Session) {
+        self.eq_input = session.eq_input;
+        self.refresh();
+        self.selected_stoich_mode = session.stoich_mode;
+        self.stoich_input_strings = session.stoich_input_strings;
+        self.stoich_input_reactants = session.stoich_input_reactants;
+        self.stoich_calculator =
+            StoichCalculator::new(self.eq_display.clone(), self.stoich_input_reactants.clone());
+        self.stoich_calculator.outputs = session.outputs;
+        self.stoich_calculator.results = session.results;
+    }
+    /// Rebalances and reanalyses the equation, but only when its input or scale has changed.
+    fn refresh(&mut self) {
+        if let Some((input, scale)) = &self.analysed {
+            if *input == self.eq_input && *scale == self.coefficient_scale {
+                return;
+            }
+        }
+        self.eq_display = balance::rescale(
+            Balancer::balance_real_time(&self.eq_input),
+            self.coefficient_scale,
+        );
+        self.analysis = Analysis::new(&self.eq_input, &self.eq_display);
+        self.analysed = Some((self.eq_input.clone(), self.coefficient_scale));
+    }
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
+        self.refresh();
+        egui::SidePanel::right("history").show(ctx, |ui| {
+            display_history(ui, self);
+        });
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("stoic");
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.session_path)
+                        .hint_text("Session file")
+                        .desired_width(160.0),
+                );
+                if ui.button("Save Session").clicked() {
+                    self.session_status = match self.session().save_to(&self.session_path) {
+                        Ok(()) => format!("saved to {}", self.session_path),
+                        Err(e) => e,
+                    };
+                }
+                if ui.button("Load Session").clicked() {
+                    self.session_status = match Session::load_from(&self.session_path) {
+                        Ok(session) => {
+                            self.restore(session);
+                            format!("loaded {}", self.session_path)
+                        }
+                        Err(e) => e,
+                    };
+                }
+                ui.label(&self.session_status);
+            });
             ui.separator();
             ui.horizontal(|ui| {
-                ui.add(egui::TextEdit::singleline(&mut self.eq_input).hint_text("Equation"));
-                self.eq_display = Balancer::balance_real_time(&self.eq_input);
+                let response =
+                    ui.add(egui::TextEdit::singleline(&mut self.eq_input).hint_text("Equation"));
+                self.refresh();
+                if response.lost_focus() && !self.eq_display.terms.is_empty() {
+                    self.history.record(
+                        EntryKind::Balanced,
+                        self.eq_display.to_string(),
+                        self.session(),
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut self.unicode_formulas, "Unicode subscripts");
+                display_scale(ui, self);
+                ui.checkbox(&mut self.decimal_coefficients, "Decimal");
+                if ui.button("Copy Equation").clicked() {
+                    let text = if self.decimal_coefficients {
+                        self.eq_display.decimal(self.unicode_formulas)
+                    } else if self.unicode_formulas {
+                        self.eq_display.unicode()
+                    } else {
+                        self.eq_display.to_string()
+                    };
+                    ui.output_mut(|o| o.copied_text = text);
+                }
             });
             ui.add_space(10.0);
             display_chem_eq(ui, self);
+            display_check(ui, &self.analysis.imbalances);
+            let types = &self.analysis.types;
+            if !types.is_empty() {
+                let types = types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+                ui.label(format!("Reaction type: {}", types.join(", ")));
+            }
             egui::ComboBox::from_label("Stoichiometry Mode")
                 .selected_text(format!("{:?}", self.selected_stoich_mode))
                 .show_ui(ui, |ui| {
@@ -73,7 +297,7 @@ impl eframe::App for App {
                         ui.horizontal(|ui| {
                             ui.add(
                                 egui::TextEdit::singleline(&mut self.stoich_input_strings[i].0)
-                                    .hint_text(format!("{}", cpd.raw()))
+                                    .hint_text(cpd.raw())
                                     .desired_width(60.0),
                             );
                             let input = &mut self.stoich_input_strings[i]
@@ -106,21 +330,62 @@ impl eframe::App for App {
                                         react => react.clone(),
                                     }
                             }
-                            self.stoich_calculator.eq = self.eq_display.clone();
-                            self.stoich_calculator.inputs = self.stoich_input_reactants.clone();
                         });
                     }
                 }
                 StoichMode::ReactantUnknown => (),
             }
             if ui.button("Stoich Time!").clicked() {
+                self.stoich_calculator = StoichCalculator::new(
+                    self.eq_display.clone(),
+                    self.stoich_input_reactants[0..self.eq_display.terms.len()].to_vec(),
+                );
                 self.stoich_calculator.product_unknown();
+                self.history.record(
+                    EntryKind::StoichRun,
+                    self.eq_display.to_string(),
+                    self.session(),
+                );
             }
-            for op in &self.stoich_calculator.outputs {
-                ui.label(op.to_string());
+            if !self.stoich_calculator.results.is_empty() {
+                ui.add_space(10.0);
+                display_results(ui, &self.stoich_calculator.results);
+                if ui.button("Copy Results").clicked() {
+                    let text = export::results_tsv(&self.stoich_calculator);
+                    ui.output_mut(|o| o.copied_text = text);
+                }
             }
+            ui.add_space(10.0);
+            display_export(ui, self);
+            ui.add_space(10.0);
+            egui::CollapsingHeader::new("Equilibrium").show(ui, |ui| {
+                display_equilibrium(ui, self);
+            });
+            egui::CollapsingHeader::new("Thermochemistry").show(ui, |ui| {
+                display_thermo(ui, self);
+            });
+            egui::CollapsingHeader::new("Hess's Law").show(ui, |ui| {
+                display_hess(ui, self);
+            });
+            egui::CollapsingHeader::new("Empirical Formula").show(ui, |ui| {
+                display_empirical(ui, self);
+            });
+            egui::CollapsingHeader::new("Combustion Analysis").show(ui, |ui| {
+                display_combustion(ui, self);
+            });
+            egui::CollapsingHeader::new("Ionic Equations").show(ui, |ui| {
+                display_ionic(ui, self);
+            });
+            egui::CollapsingHeader::new("Mass Spectrum").show(ui, |ui| {
+                display_mass_spectrum(ui, self);
+            });
         });
     }
+
+    fn save(&mut self, storage: &mut dyn Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.session());
+        eframe::set_value(storage, HISTORY_KEY, &self.history);
+    }
 }
 
 pub(crate) fn load_icon() -> egui::IconData {
@@ -142,32 +407,820 @@ pub(crate) fn load_icon() -> egui::IconData {
 }
 
 fn display_chem_eq(ui: &mut Ui, app: &mut App) {
+    let analysis = &app.analysis;
     ui.horizontal(|ui| {
-        let mut iter = app.eq_display.terms[0..app.eq_display.rhs_ix]
-            .iter()
-            .peekable();
-        while let Some(cpd) = iter.next() {
-            display_cpd(ui, cpd);
-            if let None = iter.peek() {
-                ui.label("=");
-                break;
-            }
-            ui.label("+");
-        }
-        let mut iter = app.eq_display.terms[app.eq_display.rhs_ix..app.eq_display.terms.len()]
-            .iter()
-            .peekable();
-        while let Some(cpd) = iter.next() {
-            display_cpd(ui, cpd);
-            if let Some(_) = iter.peek() {
+        let rhs_ix = app.eq_display.rhs_ix;
+        for (i, cpd) in app.eq_display.terms.iter().enumerate() {
+            if i == rhs_ix {
+                ui.label(app.eq_display.arrow.unicode());
+            } else if i > 0 {
                 ui.label("+");
             }
+            let product = i >= rhs_ix;
+            let changes = analysis
+                .changes
+                .iter()
+                .filter(|c| {
+                    if product {
+                        c.product == i
+                    } else {
+                        c.reactant == i
+                    }
+                })
+                .collect::<Vec<_>>();
+            if app.decimal_coefficients && !cpd.coefficient.is_integer() {
+                ui.label(parser::decimal(cpd.coefficient));
+                let mut cpd = cpd.clone();
+                cpd.coefficient = Ratio::one();
+                display_cpd(ui, &cpd, app, i, &changes, product);
+            } else {
+                display_cpd(ui, cpd, app, i, &changes, product);
+            }
         }
     });
+    display_notes(ui, &app.eq_display.terms);
+}
+
+/// Shows how any ambiguous symbols, such as Pr, were read.
+fn display_notes(ui: &mut Ui, terms: &[Compound]) {
+    for note in terms.iter().flat_map(|cpd| &cpd.notes) {
+        ui.label(RichText::new(note).weak());
+    }
+}
+
+fn display_scale(ui: &mut Ui, app: &mut App) {
+    let label = |scale: Scale| match scale {
+        Scale::AsWritten => "As written".to_string(),
+        Scale::Integers => "Whole numbers".to_string(),
+        Scale::PerSpecies(i) => match app.eq_display.terms.get(i) {
+            Some(cpd) => format!("Per mol {}", cpd.raw()),
+            None => "As written".to_string(),
+        },
+    };
+    egui::ComboBox::from_label("Coefficients")
+        .selected_text(label(app.coefficient_scale))
+        .show_ui(ui, |ui| {
+            let mut scales = vec![Scale::AsWritten, Scale::Integers];
+            scales.extend((0..app.eq_display.terms.len()).map(Scale::PerSpecies));
+            for scale in scales {
+                ui.selectable_value(&mut app.coefficient_scale, scale, label(scale));
+            }
+        });
+}
+
+/// Lists what does not balance when the coefficients typed in are not all one.
+fn written_imbalances(input: &str) -> Result<Vec<String>, String> {
+    let written = match parser::parse(input) {
+        Ok(written) => written,
+        Err(_) => return Ok(Vec::new()),
+    };
+    if written.terms.iter().all(|cpd| cpd.coefficient.is_one()) {
+        return Ok(Vec::new());
+    }
+    Ok(balance::check(&written)?
+        .iter()
+        .map(|i| i.to_string())
+        .collect())
+}
+
+/// Explains why the balanced equation differs when the coefficients typed in do not balance.
+fn display_check(ui: &mut Ui, imbalances: &Result<Vec<String>, String>) {
+    let imbalances = match imbalances {
+        Ok(imbalances) => imbalances,
+        Err(e) => {
+            ui.label(RichText::new(e).weak());
+            return;
+        }
+    };
+    if !imbalances.is_empty() {
+        ui.label(
+            RichText::new(format!(
+                "The coefficients as written do not balance: {}",
+                imbalances.join("; ")
+            ))
+            .weak(),
+        );
+    }
 }
-fn display_cpd(ui: &mut Ui, cpd: &Compound) {
+
+/// Shows a compound with its name and molar mass and, in a redox reaction, whether a reactant
+/// is oxidised or reduced or a product is what it becomes.
+fn display_cpd(
+    ui: &mut Ui,
+    cpd: &Compound,
+    app: &App,
+    i: usize,
+    changes: &[&RedoxChange],
+    product: bool,
+) {
     ui.vertical(|ui| {
-        ui.label(cpd.to_string());
+        let response = if app.unicode_formulas {
+            ui.label(cpd.unicode())
+        } else {
+            ui.label(formula_layout(ui, cpd))
+        };
+        response.on_hover_ui(|ui| display_composition(ui, cpd, &app.analysis.compositions[i]));
+        if let Some(name) = &app.analysis.names[i] {
+            ui.label(RichText::new(name).small().weak());
+        }
         ui.label(format!("{:.2}", cpd.molar_mass));
+        let oxidised = changes.iter().any(|c| c.is_oxidation());
+        let reduced = changes.iter().any(|c| !c.is_oxidation());
+        let role = match (oxidised, reduced, product) {
+            (true, true, false) => RichText::new("oxidised + reduced").color(Color32::GOLD),
+            (true, false, false) => RichText::new("oxidised").color(Color32::LIGHT_RED),
+            (false, true, false) => RichText::new("reduced").color(Color32::LIGHT_BLUE),
+            (true, true, true) => {
+                RichText::new("oxidation + reduction product").color(Color32::GOLD)
+            }
+            (true, false, true) => RichText::new("oxidation product").color(Color32::LIGHT_RED),
+            (false, true, true) => RichText::new("reduction product").color(Color32::LIGHT_BLUE),
+            (false, false, _) => return,
+        };
+        ui.label(role.small()).on_hover_ui(|ui| {
+            for change in changes {
+                ui.label(format!(
+                    "{}: {} → {}",
+                    change.element,
+                    redox::signed(change.from),
+                    redox::signed(change.to)
+                ));
+            }
+        });
+    });
+}
+
+fn display_composition(ui: &mut Ui, cpd: &Compound, shares: &[(ElementShare, OxidationState)]) {
+    ui.strong(format!("{} — {:.4} g/mol", cpd.raw(), cpd.molar_mass));
+    egui::Grid::new("composition").striped(true).show(ui, |ui| {
+        for header in ["Element", "Atoms", "g/mol", "Mass %", "Ox. state"] {
+            ui.strong(header);
+        }
+        ui.end_row();
+        for (share, state) in shares {
+            ui.label(&share.element);
+            ui.label(parser::decimal(share.count));
+            ui.label(format!("{:.4}", share.mass));
+            ui.label(format!("{:.2}%", share.percent));
+            let mark = match (state.assumed, state.average) {
+                (true, _) => " ?",
+                (false, true) => " (avg)",
+                (false, false) => "",
+            };
+            ui.label(format!("{}{}", state.display_state(), mark));
+            ui.end_row();
+        }
+    });
+}
+
+/// Lays out a compound with real subscripts and superscripts: smaller text aligned to the
+/// bottom or top of the line.
+fn formula_layout(ui: &Ui, cpd: &Compound) -> LayoutJob {
+    let size = TextStyle::Body.resolve(ui.style()).size;
+    let color = ui.visuals().text_color();
+    let format = |size: f32, valign: Align| TextFormat {
+        font_id: FontId::proportional(size),
+        color,
+        valign,
+        ..Default::default()
+    };
+    let mut job = LayoutJob::default();
+    for segment in cpd.segments() {
+        match segment {
+            Segment::Coefficient(s) | Segment::Symbol(s) | Segment::Multiplier(s) => {
+                job.append(&s, 0.0, format(size, Align::Center))
+            }
+            Segment::Subscript(s) => job.append(&s, 0.0, format(size * 0.7, Align::BOTTOM)),
+            Segment::Charge(s) => job.append(&s, 0.0, format(size * 0.7, Align::TOP)),
+            Segment::Isotope(a, s) => {
+                job.append(&a, 0.0, format(size * 0.7, Align::TOP));
+                job.append(&s, 0.0, format(size, Align::Center));
+            }
+            Segment::Dot => job.append("·", 0.0, format(size, Align::Center)),
+            Segment::Phase(p) => job.append(&format!("({})", p), 2.0, format(size, Align::Center)),
+        }
+    }
+    job
+}
+
+fn display_results(ui: &mut Ui, results: &[SpeciesResult]) {
+    egui::Grid::new("results")
+        .striped(true)
+        .spacing([16.0, 4.0])
+        .show(ui, |ui| {
+            for header in [
+                "Species",
+                "Role",
+                "Initial (mol)",
+                "Change (mol)",
+                "Final (mol)",
+                "Initial (g)",
+                "Change (g)",
+                "Final (g)",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for result in results {
+                let cell = |text: String| {
+                    let text = RichText::new(text);
+                    if result.role == Role::Limiting {
+                        text.color(Color32::LIGHT_RED).strong()
+                    } else {
+                        text
+                    }
+                };
+                ui.label(cell(result.species.clone()));
+                ui.label(cell(format!("{:?}", result.role)));
+                ui.label(cell(format_amount(result.initial)));
+                ui.label(cell(format!("{:+.4}", result.change)));
+                ui.label(cell(format_amount(result.final_amount)));
+                ui.label(cell(format_amount(result.initial.map(|m| result.grams(m)))));
+                ui.label(cell(format!("{:+.4}", result.grams(result.change))));
+                ui.label(cell(format_amount(
+                    result.final_amount.map(|m| result.grams(m)),
+                )));
+                ui.end_row();
+            }
+        });
+}
+
+fn display_export(ui: &mut Ui, app: &mut App) {
+    ui.horizontal(|ui| {
+        egui::ComboBox::from_label("Export Format")
+            .selected_text(format!("{:?}", app.export_format))
+            .show_ui(ui, |ui| {
+                for format in Format::ALL {
+                    ui.selectable_value(&mut app.export_format, format, format!("{:?}", format));
+                }
+            });
+        if ui.button("Export").clicked() {
+            let calculator = Some(&app.stoich_calculator).filter(|calc| !calc.results.is_empty());
+            let eq = calculator.map_or(&app.eq_display, |calc| &calc.eq);
+            app.export_output = export::export(app.export_format, eq, calculator);
+        }
+        if !app.export_output.is_empty() && ui.button("Save Export").clicked() {
+            let path = format!("stoic_export.{}", app.export_format.extension());
+            app.session_status = match std::fs::write(&path, &app.export_output) {
+                Ok(()) => format!("exported to {}", path),
+                Err(e) => format!("error writing export: {}", e),
+            };
+        }
+    });
+    if !app.export_output.is_empty() {
+        ui.add(
+            egui::TextEdit::multiline(&mut app.export_output.as_str())
+                .code_editor()
+                .desired_width(f32::INFINITY),
+        );
+    }
+}
+
+fn display_equilibrium(ui: &mut Ui, app: &mut App) {
+    ui.label("Concentrations (M) or partial pressures (atm), one per species");
+    app.equilibrium_inputs
+        .resize(app.eq_display.terms.len(), String::new());
+    ui.horizontal_wrapped(|ui| {
+        for (cpd, input) in app
+            .eq_display
+            .terms
+            .iter()
+            .zip(app.equilibrium_inputs.iter_mut())
+        {
+            ui.add(
+                egui::TextEdit::singleline(input)
+                    .hint_text(cpd.raw())
+                    .desired_width(80.0),
+            );
+        }
+    });
+    let amounts = || {
+        app.equilibrium_inputs
+            .iter()
+            .map(|input| {
+                input
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid amount '{}'", input))
+            })
+            .collect::<Result<Vec<_>, _>>()
+    };
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(&mut app.k_input)
+                .hint_text("K")
+                .desired_width(80.0),
+        );
+        if ui.button("Solve ICE Table").clicked() {
+            app.equilibrium_output = amounts().and_then(|initial| {
+                let k = app
+                    .k_input
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid K '{}'", app.k_input))?;
+                equilibrium::solve(&app.eq_display, &initial, k).map(Some)
+            });
+        }
+        if ui
+            .button("Compute K")
+            .on_hover_text("Treat the amounts as measured equilibrium amounts")
+            .clicked()
+        {
+            app.k_output = match amounts()
+                .and_then(|amounts| equilibrium::equilibrium_constant(&app.eq_display, &amounts))
+            {
+                Ok(k) => format!("K = {:.4e}", k),
+                Err(e) => e,
+            };
+        }
+        ui.label(&app.k_output);
     });
+    match &app.equilibrium_output {
+        Ok(Some(table)) => {
+            egui::Grid::new("ice").striped(true).show(ui, |ui| {
+                for header in ["Species", "Initial", "Change", "Equilibrium"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for row in &table.rows {
+                    ui.label(&row.species);
+                    ui.label(format!("{:.4e}", row.initial));
+                    ui.label(format!("{:+.4e}", row.change));
+                    ui.label(format!("{:.4e}", row.equilibrium));
+                    ui.end_row();
+                }
+            });
+            ui.label(format!("Extent of reaction: {:.4e}", table.extent));
+        }
+        Ok(None) => (),
+        Err(e) => {
+            ui.label(e);
+        }
+    }
+}
+
+fn display_thermo(ui: &mut Ui, app: &mut App) {
+    if app.eq_display.terms.is_empty() {
+        return;
+    }
+    for i in thermo::missing(&app.eq_display, &app.thermo_user) {
+        let species = app.eq_display.terms[i].raw();
+        let inputs = app.thermo_inputs.entry(species.clone()).or_default();
+        ui.horizontal(|ui| {
+            ui.label(&species);
+            for (input, hint) in
+                inputs
+                    .iter_mut()
+                    .zip(["ΔHf° kJ/mol", "S° J/(mol·K)", "ΔGf° kJ/mol"])
+            {
+                ui.add(
+                    egui::TextEdit::singleline(input)
+                        .hint_text(hint)
+                        .desired_width(90.0),
+                );
+            }
+            let values = inputs
+                .iter()
+                .map(|input| input.trim().parse::<f64>())
+                .collect::<Result<Vec<_>, _>>();
+            if let Ok([enthalpy, entropy, gibbs]) = values.as_deref() {
+                if ui.button("Set").clicked() {
+                    app.thermo_user.insert(
+                        species.clone(),
+                        ThermoData {
+                            enthalpy: *enthalpy,
+                            entropy: *entropy,
+                            gibbs: *gibbs,
+                        },
+                    );
+                }
+            }
+        });
+    }
+    let reaction = match thermo::reaction(&app.eq_display, &app.thermo_user) {
+        Ok(reaction) => reaction,
+        Err(e) => {
+            ui.label(e);
+            return;
+        }
+    };
+    ui.label(format!("ΔH° = {:.1} kJ", reaction.delta_h));
+    ui.label(format!("ΔS° = {:.1} J/K", reaction.delta_s));
+    ui.label(format!("ΔG° = {:.1} kJ", reaction.delta_g));
+    ui.horizontal(|ui| {
+        ui.label("ΔG at");
+        ui.add(egui::TextEdit::singleline(&mut app.temperature_input).desired_width(60.0));
+        ui.label("K:");
+        if let Ok(kelvin) = app.temperature_input.trim().parse::<f64>() {
+            ui.label(format!("{:.1} kJ", reaction.delta_g_at(kelvin)));
+        }
+    });
+    let calculator = &app.stoich_calculator;
+    if calculator.eq.to_string() == app.eq_display.to_string() {
+        if let Some(heat) = reaction.heat(calculator) {
+            let direction = if heat < 0.0 { "released" } else { "absorbed" };
+            ui.label(format!(
+                "Heat {} by last run: {:.2} kJ",
+                direction,
+                heat.abs()
+            ));
+        }
+    }
+}
+
+fn display_hess(ui: &mut Ui, app: &mut App) {
+    ui.label("Known reactions and their ΔH (kJ); the target is the equation above");
+    let mut remove = None;
+    for (i, (reaction, delta_h)) in app.hess_inputs.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(reaction).hint_text("Reaction"));
+            ui.add(
+                egui::TextEdit::singleline(delta_h)
+                    .hint_text("ΔH")
+                    .desired_width(70.0),
+            );
+            if ui.button("−").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        app.hess_inputs.remove(i);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("Add Reaction").clicked() {
+            app.hess_inputs.push((String::new(), String::new()));
+        }
+        if ui.button("Solve").clicked() {
+            app.hess_output = app
+                .hess_inputs
+                .iter()
+                .filter(|(reaction, _)| !reaction.trim().is_empty())
+                .map(|(reaction, delta_h)| {
+                    let delta_h = delta_h
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("invalid ΔH '{}'", delta_h))?;
+                    Ok((Balancer::balance(reaction)?, delta_h))
+                })
+                .collect::<Result<Vec<_>, String>>()
+                .and_then(|reactions| hess::solve(&app.eq_display, &reactions))
+                .map(Some);
+        }
+    });
+    match &app.hess_output {
+        Ok(Some(solution)) => {
+            for step in &solution.steps {
+                ui.label(format!(
+                    "{} × ({})  ΔH = {:.1} kJ",
+                    step.multiplier, step.equation, step.delta_h
+                ));
+            }
+            ui.strong(format!("ΔH = {:.1} kJ", solution.delta_h));
+        }
+        Ok(None) => (),
+        Err(e) => {
+            ui.label(e);
+        }
+    }
+}
+
+fn display_empirical(ui: &mut Ui, app: &mut App) {
+    ui.label("Mass percent or grams of each element");
+    let mut remove = None;
+    for (i, (element, amount)) in app.empirical_inputs.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(element)
+                    .hint_text("Element")
+                    .desired_width(60.0),
+            );
+            ui.add(
+                egui::TextEdit::singleline(amount)
+                    .hint_text("% or g")
+                    .desired_width(70.0),
+            );
+            if ui.button("−").clicked() {
+                remove = Some(i);
+            }
+        });
+    }
+    if let Some(i) = remove {
+        app.empirical_inputs.remove(i);
+    }
+    ui.horizontal(|ui| {
+        if ui.button("Add Element").clicked() {
+            app.empirical_inputs.push((String::new(), String::new()));
+        }
+        ui.add(
+            egui::TextEdit::singleline(&mut app.empirical_molar_mass)
+                .hint_text("Molar mass (optional)")
+                .desired_width(140.0),
+        );
+        if ui.button("Find Formula").clicked() {
+            app.empirical_output = app
+                .empirical_inputs
+                .iter()
+                .filter(|(element, _)| !element.trim().is_empty())
+                .map(|(element, amount)| {
+                    let amount = amount
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| format!("invalid amount '{}'", amount))?;
+                    Ok((element.trim().to_string(), amount))
+                })
+                .collect::<Result<Vec<_>, String>>()
+                .and_then(|amounts| {
+                    let empirical = composition::empirical_formula(&amounts, DEFAULT_TOLERANCE)?;
+                    let mut formulas = vec![("Empirical", empirical.clone())];
+                    if !app.empirical_molar_mass.trim().is_empty() {
+                        let molar_mass =
+                            app.empirical_molar_mass
+                                .trim()
+                                .parse::<f64>()
+                                .map_err(|_| {
+                                    format!("invalid molar mass '{}'", app.empirical_molar_mass)
+                                })?;
+                        formulas.push((
+                            "Molecular",
+                            composition::molecular_formula(
+                                &empirical,
+                                molar_mass,
+                                DEFAULT_TOLERANCE,
+                            )?,
+                        ));
+                    }
+                    Ok(formulas)
+                });
+        }
+    });
+    match &app.empirical_output {
+        Ok(formulas) => {
+            for (label, cpd) in formulas {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", label));
+                    ui.label(formula_layout(ui, cpd));
+                    ui.label(format!("({:.2} g/mol)", cpd.molar_mass));
+                });
+            }
+        }
+        Err(e) => {
+            ui.label(e);
+        }
+    }
+}
+
+fn display_combustion(ui: &mut Ui, app: &mut App) {
+    ui.horizontal_wrapped(|ui| {
+        for (input, hint) in app.combustion_inputs.iter_mut().zip([
+            "Sample (g)",
+            "CO2 (g)",
+            "H2O (g)",
+            "N2 (g, optional)",
+            "SO2 (g, optional)",
+            "Molar mass (optional)",
+        ]) {
+            ui.add(
+                egui::TextEdit::singleline(input)
+                    .hint_text(hint)
+                    .desired_width(120.0),
+            );
+        }
+    });
+    if ui.button("Analyse").clicked() {
+        let number = |input: &String| {
+            input
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("invalid mass '{}'", input))
+        };
+        let optional = |input: &String| {
+            Some(input)
+                .filter(|input| !input.trim().is_empty())
+                .map(number)
+                .transpose()
+        };
+        let [sample, co2, h2o, n2, so2, molar_mass] = &app.combustion_inputs;
+        app.combustion_output = (|| {
+            let data = CombustionData {
+                sample: number(sample)?,
+                co2: number(co2)?,
+                h2o: number(h2o)?,
+                n2: optional(n2)?,
+                so2: optional(so2)?,
+            };
+            let result = combustion::analyse(&data, DEFAULT_TOLERANCE)?;
+            let mut formulas = vec![result.empirical];
+            if let Some(molar_mass) = optional(molar_mass)? {
+                formulas.push(composition::molecular_formula(
+                    &formulas[0],
+                    molar_mass,
+                    DEFAULT_TOLERANCE,
+                )?);
+            }
+            let equation = combustion::combustion_equation(formulas.last().unwrap())?;
+            Ok(Some(CombustionReport {
+                masses: result.masses,
+                formulas,
+                equation,
+            }))
+        })();
+    }
+    let mut use_equation = None;
+    match &app.combustion_output {
+        Ok(Some(report)) => {
+            ui.label(
+                report
+                    .masses
+                    .iter()
+                    .map(|(element, mass)| format!("{}: {:.4} g", element, mass))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+            for (label, cpd) in ["Empirical", "Molecular"].iter().zip(&report.formulas) {
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", label));
+                    ui.label(formula_layout(ui, cpd));
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label(report.equation.unicode());
+                if ui.button("Use Equation").clicked() {
+                    use_equation = Some(report.equation.to_string());
+                }
+            });
+        }
+        Ok(None) => (),
+        Err(e) => {
+            ui.label(e);
+        }
+    }
+    if let Some(equation) = use_equation {
+        app.eq_input = equation;
+    }
+}
+
+fn format_amount(amount: Option<f32>) -> String {
+    match amount {
+        Some(amount) => format!("{:.4}", amount),
+        None => "excess".to_string(),
+    }
+}
+
+fn display_ionic(ui: &mut Ui, app: &App) {
+    let ionic = match &app.analysis.ionic {
+        Ok(ionic) => ionic,
+        Err(e) => {
+            ui.label(e);
+            return;
+        }
+    };
+    let text = |eq: &ChemicalEquation| {
+        if app.unicode_formulas {
+            eq.unicode()
+        } else {
+            eq.to_string()
+        }
+    };
+    ui.label(format!("Complete: {}", text(&ionic.complete)));
+    ui.label(format!("Net: {}", text(&ionic.net)));
+    if !ionic.spectators.is_empty() {
+        ui.label(format!("Spectator ions: {}", ionic.spectators.join(", ")));
+    }
+    if !ionic.precipitates.is_empty() {
+        ui.label(format!("Precipitates: {}", ionic.precipitates.join(", ")));
+    }
+}
+
+fn display_mass_spectrum(ui: &mut Ui, app: &mut App) {
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut app.mass_input).hint_text("Formula"));
+        ui.add(
+            egui::TextEdit::singleline(&mut app.charge_input)
+                .hint_text("Charge")
+                .desired_width(60.0),
+        );
+    });
+    let unchanged = app
+        .mass_analysed
+        .as_ref()
+        .is_some_and(|(mass, charge)| *mass == app.mass_input && *charge == app.charge_input);
+    if !unchanged {
+        app.mass_output = mass_report(&app.mass_input, &app.charge_input);
+        app.mass_analysed = Some((app.mass_input.clone(), app.charge_input.clone()));
+    }
+    match &app.mass_output {
+        Ok(Some(report)) => {
+            display_notes(ui, std::slice::from_ref(&report.cpd));
+            ui.label(format!("Monoisotopic mass: {:.5} u", report.monoisotopic));
+            ui.label(format!("Average mass: {:.5} u", report.average));
+            plot_peaks(ui, &report.peaks);
+            egui::Grid::new("mass_spectrum")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("m/z");
+                    ui.label("Relative abundance");
+                    ui.end_row();
+                    for peak in &report.peaks {
+                        ui.label(format!("{:.5}", peak.mz));
+                        ui.label(format!("{:.2}%", peak.abundance));
+                        ui.end_row();
+                    }
+                });
+        }
+        Ok(None) => (),
+        Err(e) => {
+            ui.label(e);
+        }
+    }
+}
+
+fn mass_report(formula: &str, charge: &str) -> Result<Option<MassReport>, String> {
+    if formula.trim().is_empty() {
+        return Ok(None);
+    }
+    let cpd = parser::parse_compound(formula)?;
+    let charge = match charge.trim() {
+        "" => cpd.charge,
+        charge => mass_spec::parse_charge(charge)?,
+    };
+    Ok(Some(MassReport {
+        monoisotopic: mass_spec::monoisotopic_mass(&cpd)?,
+        average: mass_spec::average_mass(&cpd)?,
+        peaks: mass_spec::isotope_pattern(&cpd, charge)?,
+        cpd,
+    }))
+}
+
+/// Draws an isotope pattern as a stick spectrum, labelling the peaks above 5%.
+fn plot_peaks(ui: &mut Ui, peaks: &[Peak]) {
+    let (response, painter) = ui.allocate_painter(
+        Vec2::new(ui.available_width().min(500.0), 200.0),
+        Sense::hover(),
+    );
+    let rect = response.rect.shrink2(Vec2::new(30.0, 20.0));
+    let stroke = Stroke::new(1.0, ui.visuals().text_color());
+    let font = FontId::proportional(11.0);
+    painter.line_segment([rect.left_bottom(), rect.right_bottom()], stroke);
+    let low = peaks.first().map_or(0.0, |peak| peak.mz);
+    let high = peaks.last().map_or(0.0, |peak| peak.mz);
+    let span = (high - low).max(1.0);
+    let x = |mz: f64| rect.left() + ((mz - low) / span) as f32 * rect.width();
+    for peak in peaks {
+        let top = Pos2::new(
+            x(peak.mz),
+            rect.bottom() - peak.abundance as f32 / 100.0 * rect.height(),
+        );
+        painter.line_segment(
+            [Pos2::new(top.x, rect.bottom()), top],
+            Stroke::new(2.0, Color32::LIGHT_BLUE),
+        );
+        if peak.abundance >= 5.0 {
+            painter.text(
+                top,
+                Align2::CENTER_BOTTOM,
+                format!("{:.2}", peak.mz),
+                font.clone(),
+                stroke.color,
+            );
+        }
+    }
+    painter.text(
+        Rect::from_min_max(rect.left_bottom(), response.rect.max).center(),
+        Align2::CENTER_CENTER,
+        "m/z",
+        font,
+        stroke.color,
+    );
+}
+
+fn display_history(ui: &mut Ui, app: &mut App) {
+    ui.heading("History");
+    ui.add(egui::TextEdit::singleline(&mut app.history.search).hint_text("Search"));
+    if ui.button("Clear Unpinned").clicked() {
+        app.history.clear_unpinned();
+    }
+    ui.separator();
+    let mut restore = None;
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        for i in app.history.visible() {
+            let entry = &mut app.history.entries[i];
+            ui.horizontal(|ui| {
+                let star = if entry.pinned { "★" } else { "☆" };
+                if ui.button(star).on_hover_text("Pin").clicked() {
+                    entry.pinned = !entry.pinned;
+                }
+                if ui
+                    .selectable_label(false, entry.summary())
+                    .on_hover_text("Restore")
+                    .clicked()
+                {
+                    restore = Some(entry.session.clone());
+                }
+            });
+        }
+    });
+    if let Some(session) = restore {
+        app.restore(session);
+    }
 }
@@ -0,0 +1,128 @@
+use crate::names::{self, Salt};
+use crate::parser::{parse_compound, Arrow, ChemicalEquation, Compound, Phase, Side};
+use crate::solubility::{is_precipitate, is_soluble, is_strong_acid};
+use indexmap::IndexMap;
+use num::rational::Ratio;
+use num::{Integer, Zero};
+
+#[derive(Debug, Clone)]
+pub(crate) struct IonicEquations {
+    pub(crate) complete: ChemicalEquation,
+    pub(crate) net: ChemicalEquation,
+    pub(crate) spectators: Vec<String>,
+    pub(crate) precipitates: Vec<String>,
+}
+
+/// The ions `cpd` breaks into in water: soluble salts and strong acids, unless they are
+/// marked as solid, liquid or gas. Weak acids, molecular compounds and ions stay as they are.
+fn dissociate(cpd: &Compound) -> Option<Salt> {
+    if cpd.charge != 0 || matches!(cpd.phase, Some(Phase::Solid | Phase::Liquid | Phase::Gas)) {
+        return None;
+    }
+    let elements = cpd.counts()?;
+    if let Some(acid) = names::acid(&elements) {
+        return is_strong_acid(&acid).then_some(acid);
+    }
+    names::salt(&elements).filter(|salt| cpd.phase == Some(Phase::Aqueous) || is_soluble(salt))
+}
+
+fn ion(formula: &str, charge: isize) -> String {
+    let sign = if charge > 0 { '+' } else { '-' };
+    match charge.abs() {
+        1 => format!("{}^{}(aq)", formula, sign),
+        n => format!("{}^{}{}(aq)", formula, n, sign),
+    }
+}
+
+/// Adds up each side's species, as written, with their coefficients. Insoluble salts
+/// given without a phase are marked as solids.
+fn species(eq: &ChemicalEquation) -> [IndexMap<String, Ratio<usize>>; 2] {
+    let mut sides = [IndexMap::new(), IndexMap::new()];
+    for (i, cpd) in eq.terms.iter().enumerate() {
+        let side = &mut sides[usize::from(i >= eq.rhs_ix)];
+        match dissociate(cpd) {
+            Some(salt) => {
+                *side
+                    .entry(ion(&salt.cation, salt.cation_charge))
+                    .or_insert_with(Ratio::zero) += cpd.coefficient * salt.cations;
+                *side
+                    .entry(ion(salt.anion, salt.anion_charge))
+                    .or_insert_with(Ratio::zero) += cpd.coefficient * salt.anions;
+            }
+            None => {
+                let mut formula = cpd.raw();
+                if cpd.phase.is_none() && is_precipitate(cpd) {
+                    formula.push_str("(s)");
+                }
+                *side.entry(formula).or_insert_with(Ratio::zero) += cpd.coefficient;
+            }
+        }
+    }
+    sides
+}
+
+fn equation(
+    sides: &[IndexMap<String, Ratio<usize>>; 2],
+    arrow: Arrow,
+) -> Result<ChemicalEquation, String> {
+    let mut terms = Vec::new();
+    for (species, side) in sides.iter().zip([Side::LHS, Side::RHS]) {
+        for (formula, coefficient) in species {
+            let mut cpd = parse_compound(formula)?;
+            cpd.coefficient = *coefficient;
+            cpd.side = side.clone();
+            terms.push(cpd);
+        }
+    }
+    let rhs_ix = sides[0].len();
+    Ok(ChemicalEquation {
+        terms,
+        rhs_ix,
+        arrow,
+    })
+}
+
+/// Splits a balanced molecular equation into its complete ionic equation, with every
+/// dissolved strong electrolyte written as ions, and its net ionic equation, with the
+/// spectator ions that appear unchanged on both sides cancelled.
+pub(crate) fn ionic_equations(eq: &ChemicalEquation) -> Result<IonicEquations, String> {
+    let complete = species(eq);
+    let mut net = complete.clone();
+    let mut spectators = Vec::new();
+    for (formula, reactant) in &complete[0] {
+        if let Some(product) = complete[1].get(formula) {
+            let cancelled = *reactant.min(product);
+            for side in &mut net {
+                side[formula] -= cancelled;
+            }
+            spectators.push(formula.clone());
+        }
+    }
+    for side in &mut net {
+        side.retain(|_, coefficient| !coefficient.is_zero());
+    }
+    if net.iter().any(IndexMap::is_empty) {
+        return Err("no net reaction: every species is a spectator".to_string());
+    }
+    // The largest rational that divides every coefficient, so the net equation comes out in
+    // its smallest whole numbers even if the molecular one had fractions.
+    let coefficients = net.iter().flat_map(|side| side.values());
+    let divisor = Ratio::new(
+        coefficients.clone().fold(0, |gcd, c| gcd.gcd(c.numer())),
+        coefficients.fold(1, |lcm, c| lcm.lcm(c.denom())),
+    );
+    for side in &mut net {
+        side.values_mut().for_each(|c| *c /= divisor);
+    }
+    let precipitates = eq.terms[eq.rhs_ix..]
+        .iter()
+        .filter(|cpd| is_precipitate(cpd))
+        .map(Compound::raw)
+        .collect();
+    Ok(IonicEquations {
+        complete: equation(&complete, eq.arrow)?,
+        net: equation(&net, eq.arrow)?,
+        spectators,
+        precipitates,
+    })
+}
@@ -0,0 +1,419 @@
+use crate::stoichiometry::element_molar_mass;
+
+/// Exact masses in u and natural abundances of the isotopes of every element, from the NIST
+/// atomic weights and isotopic compositions. Elements with no stable isotopes list the
+/// isotope their standard atomic weight refers to (and a few others) with no abundance, as do
+/// common tracers such as tritium, carbon-14 and fluorine-18.
+const ISOTOPES: [(&str, usize, f64, f64); 341] = [
+    ("H", 1, 1.007_825_032_23, 0.999_885),
+    ("H", 2, 2.014_101_778_12, 0.000_115),
+    ("H", 3, 3.016_049_277_9, 0.0),
+    ("He", 3, 3.016_029_320_1, 0.000_001_34),
+    ("He", 4, 4.002_603_254_13, 0.999_998_66),
+    ("Li", 6, 6.015_122_887_4, 0.075_9),
+    ("Li", 7, 7.016_003_436_6, 0.924_1),
+    ("Be", 9, 9.012_183_065, 1.0),
+    ("B", 10, 10.012_936_95, 0.199),
+    ("B", 11, 11.009_305_36, 0.801),
+    ("C", 11, 11.011_433_6, 0.0),
+    ("C", 12, 12.0, 0.989_3),
+    ("C", 13, 13.003_354_835_07, 0.010_7),
+    ("C", 14, 14.003_241_988_4, 0.0),
+    ("N", 14, 14.003_074_004_43, 0.996_36),
+    ("N", 15, 15.000_108_898_88, 0.003_64),
+    ("O", 16, 15.994_914_619_57, 0.997_57),
+    ("O", 17, 16.999_131_756_5, 0.000_38),
+    ("O", 18, 17.999_159_612_86, 0.002_05),
+    ("F", 18, 18.000_937_3, 0.0),
+    ("F", 19, 18.998_403_162_73, 1.0),
+    ("Ne", 20, 19.992_440_176_2, 0.904_8),
+    ("Ne", 21, 20.993_846_685, 0.002_7),
+    ("Ne", 22, 21.991_385_114, 0.092_5),
+    ("Na", 23, 22.989_769_282, 1.0),
+    ("Mg", 24, 23.985_041_697, 0.789_9),
+    ("Mg", 25, 24.985_836_976, 0.1),
+    ("Mg", 26, 25.982_592_968, 0.110_1),
+    ("Al", 27, 26.981_538_53, 1.0),
+    ("Si", 28, 27.976_926_534_65, 0.922_23),
+    ("Si", 29, 28.976_494_664_9, 0.046_85),
+    ("Si", 30, 29.973_770_136, 0.030_92),
+    ("P", 31, 30.973_761_998_42, 1.0),
+    ("P", 32, 31.973_907_64, 0.0),
+    ("S", 32, 31.972_071_174_4, 0.949_9),
+    ("S", 33, 32.971_458_909_8, 0.007_5),
+    ("S", 34, 33.967_867_004, 0.042_5),
+    ("S", 35, 34.969_032_31, 0.0),
+    ("S", 36, 35.967_080_71, 0.000_1),
+    ("Cl", 35, 34.968_852_682, 0.757_6),
+    ("Cl", 37, 36.965_902_602, 0.242_4),
+    ("Ar", 36, 35.967_545_105, 0.003_336),
+    ("Ar", 38, 37.962_732_11, 0.000_629),
+    ("Ar", 40, 39.962_383_123_7, 0.996_035),
+    ("K", 39, 38.963_706_486_4, 0.932_581),
+    ("K", 40, 39.963_998_166, 0.000_117),
+    ("K", 41, 40.961_825_257_9, 0.067_302),
+    ("Ca", 40, 39.962_590_863, 0.969_41),
+    ("Ca", 42, 41.958_617_83, 0.006_47),
+    ("Ca", 43, 42.958_766_44, 0.001_35),
+    ("Ca", 44, 43.955_481_56, 0.020_86),
+    ("Ca", 46, 45.953_689, 0.000_04),
+    ("Ca", 48, 47.952_522_76, 0.001_87),
+    ("Sc", 45, 44.955_908_28, 1.0),
+    ("Ti", 46, 45.952_627_72, 0.082_5),
+    ("Ti", 47, 46.951_758_79, 0.074_4),
+    ("Ti", 48, 47.947_941_98, 0.737_2),
+    ("Ti", 49, 48.947_865_68, 0.054_1),
+    ("Ti", 50, 49.944_786_89, 0.051_8),
+    ("V", 50, 49.947_156_01, 0.002_5),
+    ("V", 51, 50.943_957_04, 0.997_5),
+    ("Cr", 50, 49.946_041_83, 0.043_45),
+    ("Cr", 52, 51.940_506_23, 0.837_89),
+    ("Cr", 53, 52.940_648_15, 0.095_01),
+    ("Cr", 54, 53.938_879_16, 0.023_65),
+    ("Mn", 55, 54.938_043_91, 1.0),
+    ("Fe", 54, 53.939_608_99, 0.058_45),
+    ("Fe", 56, 55.934_936_33, 0.917_54),
+    ("Fe", 57, 56.935_392_84, 0.021_19),
+    ("Fe", 58, 57.933_274_43, 0.002_82),
+    ("Co", 59, 58.933_194_29, 1.0),
+    ("Ni", 58, 57.935_342_41, 0.680_77),
+    ("Ni", 60, 59.930_785_88, 0.262_23),
+    ("Ni", 61, 60.931_055_57, 0.011_399),
+    ("Ni", 62, 61.928_345_37, 0.036_346),
+    ("Ni", 64, 63.927_966_82, 0.009_255),
+    ("Cu", 63, 62.929_597_72, 0.691_5),
+    ("Cu", 65, 64.927_789_7, 0.308_5),
+    ("Zn", 64, 63.929_142_01, 0.491_7),
+    ("Zn", 66, 65.926_033_81, 0.277_3),
+    ("Zn", 67, 66.927_127_75, 0.040_4),
+    ("Zn", 68, 67.924_844_55, 0.184_5),
+    ("Zn", 70, 69.925_319_2, 0.006_1),
+    ("Ga", 69, 68.925_573_5, 0.601_08),
+    ("Ga", 71, 70.924_702_58, 0.398_92),
+    ("Ge", 70, 69.924_248_75, 0.205_7),
+    ("Ge", 72, 71.922_075_826, 0.274_5),
+    ("Ge", 73, 72.923_458_956, 0.077_5),
+    ("Ge", 74, 73.921_177_761, 0.365),
+    ("Ge", 76, 75.921_402_726, 0.077_3),
+    ("As", 75, 74.921_594_57, 1.0),
+    ("Se", 74, 73.922_475_934, 0.008_9),
+    ("Se", 76, 75.919_213_704, 0.093_7),
+    ("Se", 77, 76.919_914_154, 0.076_3),
+    ("Se", 78, 77.917_309_28, 0.237_7),
+    ("Se", 80, 79.916_521_8, 0.496_1),
+    ("Se", 82, 81.916_699_5, 0.087_3),
+    ("Br", 79, 78.918_337_6, 0.506_9),
+    ("Br", 81, 80.916_289_7, 0.493_1),
+    ("Kr", 78, 77.920_364_94, 0.003_55),
+    ("Kr", 80, 79.916_378_08, 0.022_86),
+    ("Kr", 82, 81.913_482_73, 0.115_93),
+    ("Kr", 83, 82.914_127_16, 0.115),
+    ("Kr", 84, 83.911_497_728_2, 0.569_87),
+    ("Kr", 86, 85.910_610_626_9, 0.172_79),
+    ("Rb", 85, 84.911_789_737_9, 0.721_7),
+    ("Rb", 87, 86.909_180_531, 0.278_3),
+    ("Sr", 84, 83.913_419_1, 0.005_6),
+    ("Sr", 86, 85.909_260_6, 0.098_6),
+    ("Sr", 87, 86.908_877_5, 0.07),
+    ("Sr", 88, 87.905_612_5, 0.825_8),
+    ("Y", 89, 88.905_840_3, 1.0),
+    ("Zr", 90, 89.904_697_7, 0.514_5),
+    ("Zr", 91, 90.905_639_6, 0.112_2),
+    ("Zr", 92, 91.905_034_7, 0.171_5),
+    ("Zr", 94, 93.906_310_8, 0.173_8),
+    ("Zr", 96, 95.908_271_4, 0.028),
+    ("Nb", 93, 92.906_373, 1.0),
+    ("Mo", 92, 91.906_807_96, 0.145_3),
+    ("Mo", 94, 93.905_084_9, 0.091_5),
+    ("Mo", 95, 94.905_838_77, 0.158_4),
+    ("Mo", 96, 95.904_676_12, 0.166_7),
+    ("Mo", 97, 96.906_018_12, 0.096),
+    ("Mo", 98, 97.905_404_82, 0.243_9),
+    ("Mo", 100, 99.907_471_8, 0.098_2),
+    ("Tc", 97, 96.906_366_7, 0.0),
+    ("Tc", 98, 97.907_212_4, 0.0),
+    ("Tc", 99, 98.906_250_8, 0.0),
+    ("Ru", 96, 95.907_590_25, 0.055_4),
+    ("Ru", 98, 97.905_286_8, 0.018_7),
+    ("Ru", 99, 98.905_934_1, 0.127_6),
+    ("Ru", 100, 99.904_214_3, 0.126),
+    ("Ru", 101, 100.905_576_9, 0.170_6),
+    ("Ru", 102, 101.904_344_1, 0.315_5),
+    ("Ru", 104, 103.905_427_5, 0.186_2),
+    ("Rh", 103, 102.905_498, 1.0),
+    ("Pd", 102, 101.905_602_2, 0.010_2),
+    ("Pd", 104, 103.904_030_5, 0.111_4),
+    ("Pd", 105, 104.905_079_6, 0.223_3),
+    ("Pd", 106, 105.903_480_4, 0.273_3),
+    ("Pd", 108, 107.903_891_6, 0.264_6),
+    ("Pd", 110, 109.905_172_2, 0.117_2),
+    ("Ag", 107, 106.905_091_6, 0.518_39),
+    ("Ag", 109, 108.904_755_3, 0.481_61),
+    ("Cd", 106, 105.906_459_9, 0.012_5),
+    ("Cd", 108, 107.904_183_4, 0.008_9),
+    ("Cd", 110, 109.903_006_61, 0.124_9),
+    ("Cd", 111, 110.904_182_87, 0.128),
+    ("Cd", 112, 111.902_762_87, 0.241_3),
+    ("Cd", 113, 112.904_408_13, 0.122_2),
+    ("Cd", 114, 113.903_365_09, 0.287_3),
+    ("Cd", 116, 115.904_763_15, 0.074_9),
+    ("In", 113, 112.904_061_84, 0.042_9),
+    ("In", 115, 114.903_878_776, 0.957_1),
+    ("Sn", 112, 111.904_823_87, 0.009_7),
+    ("Sn", 114, 113.902_782_7, 0.006_6),
+    ("Sn", 115, 114.903_344_699, 0.003_4),
+    ("Sn", 116, 115.901_742_8, 0.145_4),
+    ("Sn", 117, 116.902_953_98, 0.076_8),
+    ("Sn", 118, 117.901_606_57, 0.242_2),
+    ("Sn", 119, 118.903_311_17, 0.085_9),
+    ("Sn", 120, 119.902_201_63, 0.325_8),
+    ("Sn", 122, 121.903_443_8, 0.046_3),
+    ("Sn", 124, 123.905_276_6, 0.057_9),
+    ("Sb", 121, 120.903_812, 0.572_1),
+    ("Sb", 123, 122.904_213_2, 0.427_9),
+    ("Te", 120, 119.904_059_3, 0.000_9),
+    ("Te", 122, 121.903_043_5, 0.025_5),
+    ("Te", 123, 122.904_269_8, 0.008_9),
+    ("Te", 124, 123.902_817_1, 0.047_4),
+    ("Te", 125, 124.904_429_9, 0.070_7),
+    ("Te", 126, 125.903_310_9, 0.188_4),
+    ("Te", 128, 127.904_461_28, 0.317_4),
+    ("Te", 130, 129.906_222_748, 0.340_8),
+    ("I", 125, 124.904_629_4, 0.0),
+    ("I", 127, 126.904_471_9, 1.0),
+    ("I", 131, 130.906_126_3, 0.0),
+    ("Xe", 124, 123.905_892, 0.000_952),
+    ("Xe", 126, 125.904_298_3, 0.000_89),
+    ("Xe", 128, 127.903_531, 0.019_102),
+    ("Xe", 129, 128.904_780_861_1, 0.264_006),
+    ("Xe", 130, 129.903_509_349, 0.040_71),
+    ("Xe", 131, 130.905_084_06, 0.212_324),
+    ("Xe", 132, 131.904_155_085_6, 0.269_086),
+    ("Xe", 134, 133.905_394_66, 0.104_357),
+    ("Xe", 136, 135.907_214_484, 0.088_573),
+    ("Cs", 133, 132.905_451_961, 1.0),
+    ("Ba", 130, 129.906_320_7, 0.001_06),
+    ("Ba", 132, 131.905_061_1, 0.001_01),
+    ("Ba", 134, 133.904_508_18, 0.024_17),
+    ("Ba", 135, 134.905_688_38, 0.065_92),
+    ("Ba", 136, 135.904_575_73, 0.078_54),
+    ("Ba", 137, 136.905_827_14, 0.112_32),
+    ("Ba", 138, 137.905_247, 0.716_98),
+    ("La", 138, 137.907_114_9, 0.000_888_1),
+    ("La", 139, 138.906_356_3, 0.999_111_9),
+    ("Ce", 136, 135.907_129_21, 0.001_85),
+    ("Ce", 138, 137.905_991, 0.002_51),
+    ("Ce", 140, 139.905_443_1, 0.884_5),
+    ("Ce", 142, 141.909_250_4, 0.111_14),
+    ("Pr", 141, 140.907_657_6, 1.0),
+    ("Nd", 142, 141.907_729, 0.271_52),
+    ("Nd", 143, 142.909_82, 0.121_74),
+    ("Nd", 144, 143.910_093, 0.237_98),
+    ("Nd", 145, 144.912_579_3, 0.082_93),
+    ("Nd", 146, 145.913_122_6, 0.171_89),
+    ("Nd", 148, 147.916_899_3, 0.057_56),
+    ("Nd", 150, 149.920_902_2, 0.056_38),
+    ("Pm", 145, 144.912_755_9, 0.0),
+    ("Pm", 147, 146.915_145, 0.0),
+    ("Sm", 144, 143.912_006_5, 0.030_7),
+    ("Sm", 147, 146.914_904_4, 0.149_9),
+    ("Sm", 148, 147.914_829_2, 0.112_4),
+    ("Sm", 149, 148.917_192_1, 0.138_2),
+    ("Sm", 150, 149.917_282_9, 0.073_8),
+    ("Sm", 152, 151.919_739_7, 0.267_5),
+    ("Sm", 154, 153.922_216_9, 0.227_5),
+    ("Eu", 151, 150.919_857_8, 0.478_1),
+    ("Eu", 153, 152.921_238, 0.521_9),
+    ("Gd", 152, 151.919_799_5, 0.002),
+    ("Gd", 154, 153.920_874_1, 0.021_8),
+    ("Gd", 155, 154.922_630_5, 0.148),
+    ("Gd", 156, 155.922_131_2, 0.204_7),
+    ("Gd", 157, 156.923_968_6, 0.156_5),
+    ("Gd", 158, 157.924_112_3, 0.248_4),
+    ("Gd", 160, 159.927_062_4, 0.218_6),
+    ("Tb", 159, 158.925_354_7, 1.0),
+    ("Dy", 156, 155.924_284_7, 0.000_56),
+    ("Dy", 158, 157.924_415_9, 0.000_95),
+    ("Dy", 160, 159.925_204_6, 0.023_29),
+    ("Dy", 161, 160.926_940_5, 0.188_89),
+    ("Dy", 162, 161.926_805_6, 0.254_75),
+    ("Dy", 163, 162.928_738_3, 0.248_96),
+    ("Dy", 164, 163.929_181_9, 0.282_6),
+    ("Ho", 165, 164.930_328_8, 1.0),
+    ("Er", 162, 161.928_788_4, 0.001_39),
+    ("Er", 164, 163.929_208_8, 0.016_01),
+    ("Er", 166, 165.930_299_5, 0.335_03),
+    ("Er", 167, 166.932_054_6, 0.228_69),
+    ("Er", 168, 167.932_376_7, 0.269_78),
+    ("Er", 170, 169.935_470_2, 0.149_1),
+    ("Tm", 169, 168.934_217_9, 1.0),
+    ("Yb", 168, 167.933_889_6, 0.001_23),
+    ("Yb", 170, 169.934_766_4, 0.029_82),
+    ("Yb", 171, 170.936_330_2, 0.140_9),
+    ("Yb", 172, 171.936_385_9, 0.216_8),
+    ("Yb", 173, 172.938_215_1, 0.161_03),
+    ("Yb", 174, 173.938_866_4, 0.320_26),
+    ("Yb", 176, 175.942_576_4, 0.129_96),
+    ("Lu", 175, 174.940_775_2, 0.974_01),
+    ("Lu", 176, 175.942_689_7, 0.025_99),
+    ("Hf", 174, 173.940_046_1, 0.001_6),
+    ("Hf", 176, 175.941_407_6, 0.052_6),
+    ("Hf", 177, 176.943_227_7, 0.186),
+    ("Hf", 178, 177.943_705_8, 0.272_8),
+    ("Hf", 179, 178.945_823_2, 0.136_2),
+    ("Hf", 180, 179.946_557, 0.350_8),
+    ("Ta", 180, 179.947_464_8, 0.000_120_1),
+    ("Ta", 181, 180.947_995_8, 0.999_879_9),
+    ("W", 180, 179.946_710_8, 0.001_2),
+    ("W", 182, 181.948_203_94, 0.265),
+    ("W", 183, 182.950_222_75, 0.143_1),
+    ("W", 184, 183.950_930_92, 0.306_4),
+    ("W", 186, 185.954_362_8, 0.284_3),
+    ("Re", 185, 184.952_954_5, 0.374),
+    ("Re", 187, 186.955_750_1, 0.626),
+    ("Os", 184, 183.952_488_5, 0.000_2),
+    ("Os", 186, 185.953_835, 0.015_9),
+    ("Os", 187, 186.955_747_4, 0.019_6),
+    ("Os", 188, 187.955_835_2, 0.132_4),
+    ("Os", 189, 188.958_144_2, 0.161_5),
+    ("Os", 190, 189.958_443_7, 0.262_6),
+    ("Os", 192, 191.961_477, 0.407_8),
+    ("Ir", 191, 190.960_589_3, 0.373),
+    ("Ir", 193, 192.962_921_6, 0.627),
+    ("Pt", 190, 189.959_929_7, 0.000_12),
+    ("Pt", 192, 191.961_038_7, 0.007_82),
+    ("Pt", 194, 193.962_680_9, 0.328_6),
+    ("Pt", 195, 194.964_791_7, 0.337_8),
+    ("Pt", 196, 195.964_952_09, 0.252_1),
+    ("Pt", 198, 197.967_894_9, 0.073_56),
+    ("Au", 197, 196.966_568_79, 1.0),
+    ("Hg", 196, 195.965_832_6, 0.001_5),
+    ("Hg", 198, 197.966_768_6, 0.099_7),
+    ("Hg", 199, 198.968_280_64, 0.168_7),
+    ("Hg", 200, 199.968_326_59, 0.231),
+    ("Hg", 201, 200.970_302_84, 0.131_8),
+    ("Hg", 202, 201.970_643_4, 0.298_6),
+    ("Hg", 204, 203.973_493_98, 0.068_7),
+    ("Tl", 203, 202.972_344_6, 0.295_2),
+    ("Tl", 205, 204.974_427_8, 0.704_8),
+    ("Pb", 204, 203.973_044, 0.014),
+    ("Pb", 206, 205.974_465_7, 0.241),
+    ("Pb", 207, 206.975_897_3, 0.221),
+    ("Pb", 208, 207.976_652_5, 0.524),
+    ("Bi", 209, 208.980_399_1, 1.0),
+    ("Po", 209, 208.982_430_8, 0.0),
+    ("Po", 210, 209.982_874_1, 0.0),
+    ("At", 210, 209.987_147_9, 0.0),
+    ("Rn", 222, 222.017_578_2, 0.0),
+    ("Fr", 223, 223.019_736, 0.0),
+    ("Ra", 226, 226.025_410_3, 0.0),
+    ("Ac", 227, 227.027_752_3, 0.0),
+    ("Th", 232, 232.038_055_8, 1.0),
+    ("Pa", 231, 231.035_884_2, 1.0),
+    ("U", 233, 233.039_635_5, 0.0),
+    ("U", 234, 234.040_952_3, 0.000_054),
+    ("U", 235, 235.043_930_1, 0.007_204),
+    ("U", 236, 236.045_568_2, 0.0),
+    ("U", 238, 238.050_788_4, 0.992_742),
+    ("Np", 237, 237.048_173_6, 0.0),
+    ("Pu", 238, 238.049_560_1, 0.0),
+    ("Pu", 239, 239.052_163_6, 0.0),
+    ("Pu", 240, 240.053_813_8, 0.0),
+    ("Pu", 242, 242.058_742_8, 0.0),
+    ("Pu", 244, 244.064_205_3, 0.0),
+    ("Am", 241, 241.056_829_3, 0.0),
+    ("Am", 243, 243.061_381_3, 0.0),
+    ("Cm", 247, 247.070_354_1, 0.0),
+    ("Bk", 247, 247.070_307_3, 0.0),
+    ("Cf", 251, 251.079_588_6, 0.0),
+    ("Es", 252, 252.082_98, 0.0),
+    ("Fm", 257, 257.095_106_1, 0.0),
+    ("Md", 258, 258.098_431_5, 0.0),
+    ("No", 250, 250.087_56, 0.0),
+    ("Lr", 266, 266.119_83, 0.0),
+    ("Rf", 267, 267.121_79, 0.0),
+    ("Db", 268, 268.125_67, 0.0),
+    ("Sg", 269, 269.128_63, 0.0),
+    ("Bh", 270, 270.133_36, 0.0),
+    ("Hs", 277, 277.151_9, 0.0),
+    ("Mt", 278, 278.156_31, 0.0),
+    ("Ds", 281, 281.164_51, 0.0),
+    ("Rg", 282, 282.169_12, 0.0),
+    ("Cn", 285, 285.177_12, 0.0),
+    ("Nh", 286, 286.182_21, 0.0),
+    ("Fl", 289, 289.190_42, 0.0),
+    ("Mc", 290, 290.195_98, 0.0),
+    ("Lv", 293, 293.204_49, 0.0),
+    ("Ts", 294, 294.210_46, 0.0),
+    ("Og", 294, 294.213_92, 0.0),
+];
+
+/// The exact mass of an isotope, e.g. 13.00335 u for carbon-13.
+pub(crate) fn isotope_mass(element: &str, mass_number: usize) -> Option<f64> {
+    ISOTOPES
+        .iter()
+        .find(|(e, a, _, _)| *e == element && *a == mass_number)
+        .map(|(_, _, mass, _)| *mass)
+}
+
+/// The label an isotope is stored under in a compound's elements: D and T for hydrogen-2
+/// and -3, otherwise the mass number and symbol in brackets, e.g. [13C].
+pub(crate) fn label(element: &str, mass_number: usize) -> Result<String, String> {
+    if isotope_mass(element, mass_number).is_none() {
+        return Err(format!("no isotope data for {}-{}", element, mass_number));
+    }
+    Ok(match (element, mass_number) {
+        ("H", 2) => "D".to_string(),
+        ("H", 3) => "T".to_string(),
+        _ => format!("[{}{}]", mass_number, element),
+    })
+}
+
+/// Splits an isotope label into its element and mass number, so [13C] gives C and 13 and
+/// D gives H and 2. Plain element symbols give `None`.
+pub(crate) fn split(label: &str) -> Option<(&str, usize)> {
+    match label {
+        "D" => return Some(("H", 2)),
+        "T" => return Some(("H", 3)),
+        _ => {}
+    }
+    let label = label.strip_prefix('[')?.strip_suffix(']')?;
+    let symbol = label.find(|c: char| !c.is_ascii_digit())?;
+    let (mass_number, element) = label.split_at(symbol);
+    Some((element, mass_number.parse().ok()?))
+}
+
+/// The element an element symbol or isotope label belongs to, e.g. H for D and C for [13C].
+pub(crate) fn element(label: &str) -> &str {
+    split(label).map_or(label, |(element, _)| element)
+}
+
+/// The standard atomic weight of an element, or the exact mass of an isotope label.
+pub(crate) fn molar_mass(label: &str) -> Option<f32> {
+    match split(label) {
+        Some((element, mass_number)) => isotope_mass(element, mass_number).map(|m| m as f32),
+        None => element_molar_mass(label),
+    }
+}
+
+/// The naturally occurring isotopes of an element as mass number, exact mass and abundance.
+/// An element with no stable isotopes, such as technetium, gives the isotope its standard
+/// atomic weight refers to, e.g. technetium-98.
+pub(crate) fn natural_isotopes(element: &str) -> Vec<(usize, f64, f64)> {
+    let isotopes = ISOTOPES
+        .iter()
+        .filter(|(e, _, _, abundance)| *e == element && *abundance > 0.0)
+        .map(|(_, a, mass, abundance)| (*a, *mass, *abundance))
+        .collect::<Vec<_>>();
+    if !isotopes.is_empty() {
+        return isotopes;
+    }
+    let Some(weight) = element_molar_mass(element) else {
+        return isotopes;
+    };
+    ISOTOPES
+        .iter()
+        .find(|(e, a, _, _)| *e == element && *a as f32 == weight.round())
+        .map(|(_, a, mass, _)| vec![(*a, *mass, 1.0)])
+        .unwrap_or_default()
+}
@@ -3,12 +3,40 @@ extern crate nalgebra as na;
 use std::sync::Arc;
 
 mod balance;
+mod classify;
+mod cli;
+mod combustion;
+mod composition;
+mod equilibrium;
+mod export;
+mod hess;
+mod history;
 mod interface;
+mod ionic;
+mod isotopes;
+mod mass_spec;
 mod matrix;
+mod names;
 mod parser;
+mod predict;
+mod redox;
+mod session;
+mod solubility;
 mod stoichiometry;
+mod thermo;
 
 fn main() -> Result<(), eframe::Error> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if !args.is_empty() {
+        match cli::run(&args) {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
     let mut native_options = eframe::NativeOptions::default();
     native_options.viewport.icon = Some(Arc::new(interface::load_icon()));
     eframe::run_native(
@@ -0,0 +1,219 @@
+use crate::isotopes;
+use crate::parser::Compound;
+use std::collections::BTreeMap;
+
+const PROTON_MASS: f64 = 1.007_276_466_88;
+const ELECTRON_MASS: f64 = 0.000_548_579_909;
+/// Isotopologues less likely than this are dropped while building a pattern.
+const CUTOFF: f64 = 1e-12;
+/// Peaks below this percentage of the tallest are not reported.
+const MIN_PEAK: f64 = 0.01;
+/// Patterns take longer the more atoms there are, so larger compounds are refused rather than
+/// left to freeze the GUI.
+const MAX_ATOMS: usize = 100_000;
+
+/// One peak of an isotope pattern. The m/z is the abundance-weighted mean of the
+/// isotopologues sharing a nominal mass, and the abundance is relative to the tallest peak.
+#[derive(Debug, Clone)]
+pub(crate) struct Peak {
+    pub(crate) mz: f64,
+    pub(crate) abundance: f64,
+}
+
+/// Probability and summed mass × probability of the isotopologues at each nominal mass.
+type Distribution = BTreeMap<usize, (f64, f64)>;
+
+/// Mass number, exact mass and abundance of each isotope an element contributes.
+type Isotopes = Vec<(usize, f64, f64)>;
+
+/// The isotopes an element or label contributes: its natural isotopes, or only the labelled
+/// one for [13C], D and T.
+fn isotopes(label: &str) -> Result<Isotopes, String> {
+    if let Some((element, mass_number)) = isotopes::split(label) {
+        let mass = isotopes::isotope_mass(element, mass_number).unwrap();
+        return Ok(vec![(mass_number, mass, 1.0)]);
+    }
+    let isotopes = isotopes::natural_isotopes(label);
+    if isotopes.is_empty() {
+        return Err(format!("no isotope data for {}", label));
+    }
+    Ok(isotopes)
+}
+
+fn counts(cpd: &Compound) -> Result<Vec<(Isotopes, usize)>, String> {
+    let counts = cpd
+        .counts()
+        .ok_or("exact masses need whole-number subscripts")?;
+    counts
+        .iter()
+        .map(|(label, count)| Ok((isotopes(label)?, *count)))
+        .collect()
+}
+
+/// Mass of the electrons an ion has lost (or gained, for anions).
+fn electrons(cpd: &Compound) -> f64 {
+    cpd.charge as f64 * ELECTRON_MASS
+}
+
+/// The mass of `cpd` made only of the most abundant isotope of each element.
+pub(crate) fn monoisotopic_mass(cpd: &Compound) -> Result<f64, String> {
+    let mut mass = -electrons(cpd);
+    for (isotopes, count) in counts(cpd)? {
+        let (_, principal, _) = isotopes.iter().max_by(|a, b| a.2.total_cmp(&b.2)).unwrap();
+        mass += principal * count as f64;
+    }
+    Ok(mass)
+}
+
+/// The mass of `cpd` averaged over natural isotope abundances.
+pub(crate) fn average_mass(cpd: &Compound) -> Result<f64, String> {
+    let mut mass = -electrons(cpd);
+    for (isotopes, count) in counts(cpd)? {
+        let average = isotopes.iter().map(|(_, m, p)| m * p).sum::<f64>()
+            / isotopes.iter().map(|(_, _, p)| p).sum::<f64>();
+        mass += average * count as f64;
+    }
+    Ok(mass)
+}
+
+fn convolve(a: &Distribution, b: &Distribution) -> Distribution {
+    let mut result = Distribution::new();
+    for (nominal_a, (p_a, mass_a)) in a {
+        for (nominal_b, (p_b, mass_b)) in b {
+            let p = p_a * p_b;
+            if p < CUTOFF {
+                continue;
+            }
+            let entry = result.entry(nominal_a + nominal_b).or_default();
+            entry.0 += p;
+            // Mean masses add, so the summed mass × probability is built from each mean.
+            entry.1 += p * (mass_a / p_a + mass_b / p_b);
+        }
+    }
+    result
+}
+
+/// `distribution` convolved with itself `n` times, by repeated squaring.
+fn power(mut distribution: Distribution, mut n: usize) -> Distribution {
+    let mut result = Distribution::from([(0, (1.0, 0.0))]);
+    while n > 0 {
+        if n % 2 == 1 {
+            result = convolve(&result, &distribution);
+        }
+        distribution = convolve(&distribution, &distribution);
+        n /= 2;
+    }
+    result
+}
+
+/// The isotope pattern of `cpd` at unit resolution as the ion with `charge`. Ions are made by
+/// adding protons to (or, for negative charges, removing them from) the compound as written,
+/// so C6H12O6 at +1 is [M+H]⁺ while NH4^+ at +1 is the ion itself. A charge of 0 gives the
+/// neutral masses.
+pub(crate) fn isotope_pattern(cpd: &Compound, charge: isize) -> Result<Vec<Peak>, String> {
+    let counts = counts(cpd)?;
+    counts
+        .iter()
+        .try_fold(0usize, |atoms, (_, count)| atoms.checked_add(*count))
+        .filter(|atoms| *atoms <= MAX_ATOMS)
+        .ok_or_else(|| format!("isotope patterns are limited to {} atoms", MAX_ATOMS))?;
+    let protons = charge
+        .checked_sub(cpd.charge)
+        .ok_or_else(|| format!("the charge {} is too large", charge))?;
+    let mut distribution = Distribution::from([(0, (1.0, 0.0))]);
+    for (isotopes, count) in counts {
+        let element = isotopes
+            .into_iter()
+            .map(|(a, mass, p)| (a, (p, mass * p)))
+            .collect::<Distribution>();
+        distribution = convolve(&distribution, &power(element, count));
+    }
+    let shift = protons as f64 * PROTON_MASS - electrons(cpd);
+    let divisor = charge.unsigned_abs().max(1) as f64;
+    let tallest = distribution.values().map(|(p, _)| *p).fold(0.0, f64::max);
+    Ok(distribution
+        .values()
+        .map(|(p, mass)| Peak {
+            mz: (mass / p + shift) / divisor,
+            abundance: 100.0 * p / tallest,
+        })
+        .filter(|peak| peak.abundance >= MIN_PEAK)
+        .collect())
+}
+
+/// Parses a charge written as 2, +2, -1, 2+ or 3-.
+pub(crate) fn parse_charge(charge: &str) -> Result<isize, String> {
+    let invalid = || format!("invalid charge '{}'", charge);
+    if let Some(magnitude) = charge.strip_suffix('+') {
+        magnitude.parse().map_err(|_| invalid())
+    } else if let Some(magnitude) = charge.strip_suffix('-') {
+        magnitude
+            .parse::<isize>()
+            .map(|z| -z)
+            .map_err(|_| invalid())
+    } else {
+        charge.parse().map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    fn glucose() -> Compound {
+        parser::parse_compound("C6H12O6").unwrap()
+    }
+
+    fn assert_close(a: f64, b: f64, tolerance: f64) {
+        assert!((a - b).abs() < tolerance, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn glucose_masses() {
+        assert_close(monoisotopic_mass(&glucose()).unwrap(), 180.06339, 1e-5);
+        assert_close(average_mass(&glucose()).unwrap(), 180.156, 1e-3);
+    }
+
+    #[test]
+    fn glucose_molecular_ion_pattern() {
+        let peaks = isotope_pattern(&glucose(), 0).unwrap();
+        let nominal = peaks.iter().map(|peak| peak.mz.round()).collect::<Vec<_>>();
+        assert_eq!(nominal, [180.0, 181.0, 182.0, 183.0]);
+        for (peak, expected) in peaks.iter().zip([100.0, 6.86, 1.43, 0.09]) {
+            assert_close(peak.abundance, expected, 0.01);
+        }
+        assert_close(peaks[0].mz, 180.06339, 1e-5);
+    }
+
+    #[test]
+    fn glucose_ions_add_or_remove_protons() {
+        let protonated = isotope_pattern(&glucose(), 1).unwrap();
+        assert_close(protonated[0].mz, 181.07066, 1e-5);
+        let deprotonated = isotope_pattern(&glucose(), -1).unwrap();
+        assert_close(deprotonated[0].mz, 179.05611, 1e-5);
+        let doubly = isotope_pattern(&glucose(), 2).unwrap();
+        assert_close(doubly[0].mz, (180.06339 + 2.0 * PROTON_MASS) / 2.0, 1e-5);
+    }
+
+    #[test]
+    fn parses_charges() {
+        for (charge, z) in [("2", 2), ("+2", 2), ("-1", -1), ("2+", 2), ("3-", -3)] {
+            assert_eq!(parse_charge(charge), Ok(z));
+        }
+        assert!(parse_charge("two").is_err());
+    }
+
+    #[test]
+    fn refuses_patterns_with_too_many_atoms() {
+        let cpd = parser::parse_compound("C1000000000").unwrap();
+        assert!(isotope_pattern(&cpd, 0).is_err());
+        assert!(monoisotopic_mass(&cpd).is_ok());
+    }
+
+    #[test]
+    fn rejects_charges_that_overflow() {
+        let cpd = parser::parse_compound("SO4^2-").unwrap();
+        assert!(isotope_pattern(&cpd, isize::MAX).is_err());
+    }
+}
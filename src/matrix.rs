@@ -63,7 +63,7 @@ impl GaussianElimination {
             .collect::<Vec<_>>();
         self = self.simplify(v); // eliminate the zero rows
         var_table = (0..self.m)
-            .filter(|e| !var_table.contains(&e))
+            .filter(|e| !var_table.contains(e))
             .collect::<Vec<_>>(); // get free variables table
         var_table.iter().for_each(|x| {
             let tmp = self.matrix_a.column(*x) * -Ratio::<isize>::one();
@@ -103,12 +103,7 @@ impl GaussianElimination {
     }
 
     fn get_pivot(&self, row: usize) -> Option<usize> {
-        for column in 0..self.m {
-            if self.matrix_a[(row, column)] != Ratio::<isize>::zero() {
-                return Some(column);
-            }
-        }
-        None
+        (0..self.m).find(|column| self.matrix_a[(row, *column)] != Ratio::<isize>::zero())
     }
 
     fn get_leftmost_row(&self, row: usize) -> Option<usize> {
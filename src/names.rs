@@ -0,0 +1,724 @@
+use crate::parser::{parse_compound, Compound, Part, Phase};
+use crate::redox::{fixed_state, is_metal};
+use indexmap::IndexMap;
+use num::One;
+
+/// Element names and symbols, used both ways by the nomenclature rules.
+const ELEMENTS: [(&str, &str); 87] = [
+    ("hydrogen", "H"),
+    ("helium", "He"),
+    ("lithium", "Li"),
+    ("beryllium", "Be"),
+    ("boron", "B"),
+    ("carbon", "C"),
+    ("nitrogen", "N"),
+    ("oxygen", "O"),
+    ("fluorine", "F"),
+    ("neon", "Ne"),
+    ("sodium", "Na"),
+    ("magnesium", "Mg"),
+    ("aluminium", "Al"),
+    ("aluminum", "Al"),
+    ("silicon", "Si"),
+    ("phosphorus", "P"),
+    ("sulfur", "S"),
+    ("sulphur", "S"),
+    ("chlorine", "Cl"),
+    ("argon", "Ar"),
+    ("potassium", "K"),
+    ("calcium", "Ca"),
+    ("scandium", "Sc"),
+    ("titanium", "Ti"),
+    ("vanadium", "V"),
+    ("chromium", "Cr"),
+    ("manganese", "Mn"),
+    ("iron", "Fe"),
+    ("cobalt", "Co"),
+    ("nickel", "Ni"),
+    ("copper", "Cu"),
+    ("zinc", "Zn"),
+    ("gallium", "Ga"),
+    ("germanium", "Ge"),
+    ("arsenic", "As"),
+    ("selenium", "Se"),
+    ("bromine", "Br"),
+    ("krypton", "Kr"),
+    ("rubidium", "Rb"),
+    ("strontium", "Sr"),
+    ("yttrium", "Y"),
+    ("zirconium", "Zr"),
+    ("niobium", "Nb"),
+    ("molybdenum", "Mo"),
+    ("technetium", "Tc"),
+    ("ruthenium", "Ru"),
+    ("rhodium", "Rh"),
+    ("palladium", "Pd"),
+    ("silver", "Ag"),
+    ("cadmium", "Cd"),
+    ("indium", "In"),
+    ("tin", "Sn"),
+    ("antimony", "Sb"),
+    ("tellurium", "Te"),
+    ("iodine", "I"),
+    ("xenon", "Xe"),
+    ("caesium", "Cs"),
+    ("cesium", "Cs"),
+    ("barium", "Ba"),
+    ("lanthanum", "La"),
+    ("cerium", "Ce"),
+    ("hafnium", "Hf"),
+    ("tantalum", "Ta"),
+    ("tungsten", "W"),
+    ("rhenium", "Re"),
+    ("osmium", "Os"),
+    ("iridium", "Ir"),
+    ("platinum", "Pt"),
+    ("gold", "Au"),
+    ("mercury", "Hg"),
+    ("thallium", "Tl"),
+    ("lead", "Pb"),
+    ("bismuth", "Bi"),
+    ("polonium", "Po"),
+    ("astatine", "At"),
+    ("radon", "Rn"),
+    ("francium", "Fr"),
+    ("radium", "Ra"),
+    ("actinium", "Ac"),
+    ("thorium", "Th"),
+    ("protactinium", "Pa"),
+    ("uranium", "U"),
+    ("neptunium", "Np"),
+    ("plutonium", "Pu"),
+    ("americium", "Am"),
+    ("curium", "Cm"),
+    ("lawrencium", "Lr"),
+];
+
+/// Anions by name, with their formulas and charges. Monatomic anions come first.
+const ANIONS: [(&str, &str, isize); 41] = [
+    ("hydride", "H", -1),
+    ("fluoride", "F", -1),
+    ("chloride", "Cl", -1),
+    ("bromide", "Br", -1),
+    ("iodide", "I", -1),
+    ("oxide", "O", -2),
+    ("sulfide", "S", -2),
+    ("selenide", "Se", -2),
+    ("telluride", "Te", -2),
+    ("nitride", "N", -3),
+    ("phosphide", "P", -3),
+    ("arsenide", "As", -3),
+    ("carbide", "C", -4),
+    ("peroxide", "O2", -2),
+    ("hydroxide", "OH", -1),
+    ("cyanide", "CN", -1),
+    ("thiocyanate", "SCN", -1),
+    ("nitrate", "NO3", -1),
+    ("nitrite", "NO2", -1),
+    ("sulfate", "SO4", -2),
+    ("sulfite", "SO3", -2),
+    ("hydrogen sulfate", "HSO4", -1),
+    ("bisulfate", "HSO4", -1),
+    ("thiosulfate", "S2O3", -2),
+    ("carbonate", "CO3", -2),
+    ("hydrogen carbonate", "HCO3", -1),
+    ("bicarbonate", "HCO3", -1),
+    ("phosphate", "PO4", -3),
+    ("hydrogen phosphate", "HPO4", -2),
+    ("dihydrogen phosphate", "H2PO4", -1),
+    ("acetate", "CH3COO", -1),
+    ("oxalate", "C2O4", -2),
+    ("perchlorate", "ClO4", -1),
+    ("chlorate", "ClO3", -1),
+    ("chlorite", "ClO2", -1),
+    ("hypochlorite", "ClO", -1),
+    ("bromate", "BrO3", -1),
+    ("iodate", "IO3", -1),
+    ("permanganate", "MnO4", -1),
+    ("chromate", "CrO4", -2),
+    ("dichromate", "Cr2O7", -2),
+];
+
+const POLYATOMIC_CATIONS: [(&str, &str, isize); 2] =
+    [("ammonium", "NH4", 1), ("hydronium", "H3O", 1)];
+
+/// Traditional -ous/-ic names, understood but never generated.
+const TRADITIONAL_CATIONS: [(&str, &str, isize); 10] = [
+    ("ferrous", "Fe", 2),
+    ("ferric", "Fe", 3),
+    ("cuprous", "Cu", 1),
+    ("cupric", "Cu", 2),
+    ("stannous", "Sn", 2),
+    ("stannic", "Sn", 4),
+    ("plumbous", "Pb", 2),
+    ("plumbic", "Pb", 4),
+    ("cobaltous", "Co", 2),
+    ("cobaltic", "Co", 3),
+];
+
+/// Common and trivial names that the nomenclature rules don't cover.
+const COMMON: [(&str, &str); 53] = [
+    ("water", "H2O"),
+    ("ammonia", "NH3"),
+    ("hydrogen peroxide", "H2O2"),
+    ("ozone", "O3"),
+    ("buckminsterfullerene", "C60"),
+    ("hydrazine", "N2H4"),
+    ("phosphine", "PH3"),
+    ("silane", "SiH4"),
+    ("methane", "CH4"),
+    ("ethane", "C2H6"),
+    ("propane", "C3H8"),
+    ("butane", "C4H10"),
+    ("pentane", "C5H12"),
+    ("hexane", "C6H14"),
+    ("heptane", "C7H16"),
+    ("octane", "C8H18"),
+    ("ethene", "C2H4"),
+    ("ethylene", "C2H4"),
+    ("propene", "C3H6"),
+    ("ethyne", "C2H2"),
+    ("acetylene", "C2H2"),
+    ("benzene", "C6H6"),
+    ("methanol", "CH3OH"),
+    ("ethanol", "C2H5OH"),
+    ("propanol", "C3H7OH"),
+    ("glycerol", "C3H8O3"),
+    ("acetone", "CH3COCH3"),
+    ("formaldehyde", "HCHO"),
+    ("acetic acid", "CH3COOH"),
+    ("formic acid", "HCOOH"),
+    ("citric acid", "C6H8O7"),
+    ("glucose", "C6H12O6"),
+    ("fructose", "C6H12O6"),
+    ("sucrose", "C12H22O11"),
+    ("urea", "CO(NH2)2"),
+    ("aspirin", "C9H8O4"),
+    ("caffeine", "C8H10N4O2"),
+    ("table salt", "NaCl"),
+    ("salt", "NaCl"),
+    ("baking soda", "NaHCO3"),
+    ("washing soda", "Na2CO3"),
+    ("lye", "NaOH"),
+    ("caustic soda", "NaOH"),
+    ("quicklime", "CaO"),
+    ("lime", "CaO"),
+    ("slaked lime", "Ca(OH)2"),
+    ("limestone", "CaCO3"),
+    ("chalk", "CaCO3"),
+    ("gypsum", "CaSO4*2H2O"),
+    ("rust", "Fe2O3"),
+    ("silica", "SiO2"),
+    ("laughing gas", "N2O"),
+    ("dry ice", "CO2"),
+];
+
+const PREFIXES: [(&str, usize); 10] = [
+    ("mono", 1),
+    ("di", 2),
+    ("tri", 3),
+    ("tetra", 4),
+    ("penta", 5),
+    ("hexa", 6),
+    ("hepta", 7),
+    ("octa", 8),
+    ("nona", 9),
+    ("deca", 10),
+];
+
+const ROMAN: [&str; 8] = ["i", "ii", "iii", "iv", "v", "vi", "vii", "viii"];
+
+/// Elements that exist as molecules rather than single atoms.
+pub(crate) fn elemental(symbol: &str) -> String {
+    match symbol {
+        "H" | "N" | "O" | "F" | "Cl" | "Br" | "I" => format!("{}2", symbol),
+        "P" => "P4".to_string(),
+        "S" => "S8".to_string(),
+        _ => symbol.to_string(),
+    }
+}
+
+fn element(name: &str) -> Option<&'static str> {
+    ELEMENTS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, symbol)| *symbol)
+}
+
+/// An ion named on its own, with its formula and charge.
+fn cation(name: &str) -> Option<(String, isize)> {
+    let named = POLYATOMIC_CATIONS.iter().chain(&TRADITIONAL_CATIONS);
+    if let Some((_, formula, charge)) = named.clone().find(|(n, _, _)| *n == name) {
+        return Some((formula.to_string(), *charge));
+    }
+    if let Some((metal, numeral)) = name.strip_suffix(')').and_then(|n| n.split_once('(')) {
+        let symbol = element(metal).filter(|s| is_metal(s))?;
+        let charge = ROMAN.iter().position(|r| *r == numeral)? + 1;
+        return Some((symbol.to_string(), charge as isize));
+    }
+    let symbol = element(name).filter(|s| is_metal(s))?;
+    Some((symbol.to_string(), fixed_state(symbol)?))
+}
+
+fn anion(name: &str) -> Option<(String, isize)> {
+    ANIONS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, formula, charge)| (formula.to_string(), *charge))
+}
+
+/// Writes `count` of an ion, in parentheses if it has more than one element.
+fn repeat(formula: &str, count: isize) -> String {
+    let polyatomic = formula.chars().filter(char::is_ascii_uppercase).count() > 1
+        || formula.ends_with(|c: char| c.is_ascii_digit());
+    match count {
+        1 => formula.to_string(),
+        _ if polyatomic => format!("({}){}", formula, count),
+        _ => format!("{}{}", formula, count),
+    }
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Splits a Greek number prefix off `word`, allowing for the dropped vowel in names like
+/// monoxide and pentoxide. Words without a prefix count once.
+fn prefixed(word: &str, rest_valid: impl Fn(&str) -> bool) -> Option<(usize, &str)> {
+    if rest_valid(word) {
+        return Some((1, word));
+    }
+    PREFIXES.iter().find_map(|(prefix, n)| {
+        let rest = word
+            .strip_prefix(prefix)
+            .filter(|rest| rest_valid(rest))
+            .or_else(|| {
+                word.strip_prefix(&prefix[..prefix.len() - 1])
+                    .filter(|rest| rest.starts_with(['a', 'o']) && rest_valid(rest))
+            })?;
+        Some((*n, rest))
+    })
+}
+
+/// Binary molecular compounds named with prefixes, e.g. dinitrogen tetroxide.
+fn covalent(first: &str, second: &str) -> Option<String> {
+    let (n, first) = prefixed(first, |w| element(w).is_some_and(|s| !is_metal(s)))?;
+    let monatomic = |w: &str| anion(w).is_some_and(|(f, _)| element_symbol_only(&f));
+    let (m, second) = prefixed(second, monatomic)?;
+    let symbol = element(first)?;
+    let (anion, charge) = anion(second)?;
+    // Without prefixes, hydrogen compounds are balanced by charge (hydrogen sulfide is H2S).
+    let n = if n == 1 && m == 1 && symbol == "H" {
+        -charge as usize
+    } else {
+        n
+    };
+    let count = |n: usize| match n {
+        1 => String::new(),
+        n => n.to_string(),
+    };
+    Some(format!("{}{}{}{}", symbol, count(n), anion, count(m)))
+}
+
+fn element_symbol_only(formula: &str) -> bool {
+    ELEMENTS.iter().any(|(_, symbol)| *symbol == formula)
+}
+
+/// Acids named after their anions: hydro-ide acids (hydrochloric), -ate acids (sulfuric)
+/// and -ite acids (nitrous).
+fn acid_formula(stem: &str) -> Option<String> {
+    let candidates = |stem: &str, ending: &str| {
+        let mut names = vec![format!("{}{}", stem, ending)];
+        for infix in ["ur", "or"] {
+            if let Some(short) = stem.strip_suffix(infix) {
+                names.push(format!("{}{}", short, ending));
+            }
+        }
+        names
+    };
+    let names = if let Some(stem) = stem
+        .strip_prefix("hydro")
+        .and_then(|s| s.strip_suffix("ic"))
+    {
+        candidates(stem, "ide")
+    } else if let Some(stem) = stem.strip_suffix("ic") {
+        candidates(stem, "ate")
+    } else if let Some(stem) = stem.strip_suffix("ous") {
+        candidates(stem, "ite")
+    } else {
+        return None;
+    };
+    let (formula, charge) = names.iter().find_map(|name| anion(name))?;
+    Some(format!(
+        "H{}{}",
+        if charge == -1 {
+            String::new()
+        } else {
+            (-charge).to_string()
+        },
+        formula
+    ))
+}
+
+/// Salts named cation then anion, e.g. iron(III) sulfate or ammonium dihydrogen phosphate.
+fn ionic(cation_name: &str, anion_name: &str) -> Option<String> {
+    let (cation, positive) = cation(cation_name)?;
+    let (anion, negative) = anion(anion_name)?;
+    Some(salt_formula(&cation, positive, &anion, negative))
+}
+
+/// The neutral formula of a cation and anion with the given charges, e.g. Al and SO4 with
+/// charges 3 and -2 give Al2(SO4)3.
+pub(crate) fn salt_formula(cation: &str, positive: isize, anion: &str, negative: isize) -> String {
+    let lcm = positive * -negative / gcd(positive, -negative);
+    format!(
+        "{}{}",
+        repeat(cation, lcm / positive),
+        repeat(anion, lcm / -negative)
+    )
+}
+
+/// Looks up the formula for a compound name such as "sodium chloride", "sulfuric acid",
+/// "iron(III) oxide", "dinitrogen tetroxide" or "glucose". Names are matched ignoring case
+/// and extra spaces, and hydrates (copper(II) sulfate pentahydrate) and ions (sulfate ion)
+/// are understood.
+pub(crate) fn formula_from_name(name: &str) -> Result<String, String> {
+    let name = name
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace(" (", "(");
+    resolve(&name).ok_or_else(|| {
+        let first = name.split([' ', '(']).next().unwrap_or_default();
+        match element(first) {
+            Some(symbol) if is_metal(symbol) && fixed_state(symbol).is_none() => format!(
+                "unknown compound name '{}'; give the charge of {} in Roman numerals, e.g. {}(II)",
+                name, first, first
+            ),
+            _ => format!("unknown compound name '{}'", name),
+        }
+    })
+}
+
+fn resolve(name: &str) -> Option<String> {
+    if let Some((_, formula)) = COMMON.iter().find(|(n, _)| *n == name) {
+        return Some(formula.to_string());
+    }
+    if let Some(symbol) = element(name) {
+        return Some(elemental(symbol));
+    }
+    let words = name.split(' ').collect::<Vec<_>>();
+    let (last, rest) = words.split_last()?;
+    let rest = rest.join(" ");
+    if let Some(hydrate) = last.strip_suffix("hydrate") {
+        let water = match hydrate {
+            "" => 1,
+            prefix => {
+                PREFIXES
+                    .iter()
+                    .find(|(p, _)| *p == prefix || p.strip_suffix('a') == Some(prefix))?
+                    .1
+            }
+        };
+        let salt = resolve(&rest)?;
+        return Some(match water {
+            1 => format!("{}*H2O", salt),
+            n => format!("{}*{}H2O", salt, n),
+        });
+    }
+    if *last == "ion" || *last == "ions" {
+        let (formula, charge) = cation(&rest).or_else(|| anion(&rest))?;
+        let sign = if charge > 0 { '+' } else { '-' };
+        return Some(match charge.abs() {
+            1 => format!("{}^{}", formula, sign),
+            n => format!("{}^{}{}", formula, n, sign),
+        });
+    }
+    if *last == "acid" && words.len() == 2 {
+        return acid_formula(words[0]);
+    }
+    (1..words.len()).find_map(|split| {
+        let (first, second) = (words[..split].join(" "), words[split..].join(" "));
+        ionic(&first, &second).or_else(|| covalent(&first, &second))
+    })
+}
+
+fn element_name(symbol: &str) -> Option<&'static str> {
+    ELEMENTS
+        .iter()
+        .find(|(_, s)| *s == symbol)
+        .map(|(name, _)| *name)
+}
+
+fn counts(formula: &str) -> IndexMap<String, usize> {
+    parse_compound(formula).unwrap().counts().unwrap()
+}
+
+/// Whether `target` is exactly `x` of `cation` plus `y` of `anion`.
+fn combines(
+    target: &IndexMap<String, usize>,
+    (cation, x): (&IndexMap<String, usize>, usize),
+    (anion, y): (&IndexMap<String, usize>, usize),
+) -> bool {
+    let count = |e: &str| x * cation.get(e).unwrap_or(&0) + y * anion.get(e).unwrap_or(&0);
+    cation
+        .keys()
+        .chain(anion.keys())
+        .all(|e| target.contains_key(e))
+        && target.iter().all(|(e, n)| count(e) == *n)
+}
+
+fn numbered(prefix_count: usize, name: &str) -> String {
+    let prefix = PREFIXES[prefix_count - 1].0;
+    match prefix.strip_suffix(['a', 'o']) {
+        Some(short) if name.starts_with('o') => format!("{}{}", short, name),
+        _ => format!("{}{}", prefix, name),
+    }
+}
+
+/// Names `cpd` from its formula: ions, acids, salts (with Stock numerals for metals that form
+/// more than one cation), binary molecular compounds, hydrates and common compounds such as
+/// water. Returns `None` for anything the rules don't cover, such as most organic compounds.
+pub(crate) fn name(cpd: &Compound) -> Option<String> {
+    let elements = &cpd.counts()?;
+    if cpd.units.len() > 1 {
+        return hydrate_name(cpd);
+    }
+    if cpd.charge != 0 {
+        return ion_name(cpd).map(|name| format!("{} ion", name));
+    }
+    let common = || {
+        COMMON
+            .iter()
+            .find(|(_, formula)| counts(formula) == *elements)
+            .map(|(name, _)| name.to_string())
+    };
+    if elements.len() == 1 {
+        return common().or_else(|| element_form(elements));
+    }
+    acid_name(cpd, elements)
+        .or_else(|| salt_name(elements))
+        .or_else(|| molecular_name(cpd, elements))
+        .or_else(common)
+}
+
+/// Names the first formula unit followed by the water of crystallisation, e.g.
+/// copper(II) sulfate pentahydrate.
+fn hydrate_name(cpd: &Compound) -> Option<String> {
+    let mut water = 0;
+    for unit in &cpd.units[1..] {
+        let elements = unit
+            .elements()
+            .map(|(e, sub)| (e, sub.to_integer()))
+            .collect::<Vec<_>>();
+        if elements != [("H", 2), ("O", 1)] {
+            return None;
+        }
+        water += unit.count;
+    }
+    let mut formula = String::new();
+    for part in &cpd.units[0].parts {
+        match part {
+            Part::Element(elem, sub) if sub.is_one() => formula.push_str(elem),
+            Part::Element(elem, sub) => formula.push_str(&format!("{}{}", elem, sub)),
+            Part::Group(name, sub) if sub.is_one() => formula.push_str(name),
+            Part::Group(name, sub) => formula.push_str(&format!("{}{}", name, sub)),
+            Part::Open => formula.push('('),
+            Part::Close(1) => formula.push(')'),
+            Part::Close(count) => formula.push_str(&format!("){}", count)),
+        }
+    }
+    let salt = name(&parse_compound(&formula).ok()?)?;
+    (water <= PREFIXES.len()).then(|| format!("{} {}", salt, numbered(water, "hydrate")))
+}
+
+fn ion_name(cpd: &Compound) -> Option<String> {
+    let elements = cpd.counts()?;
+    let ions = POLYATOMIC_CATIONS.iter().chain(&ANIONS);
+    if let Some((name, _, _)) = ions
+        .clone()
+        .find(|(_, formula, charge)| *charge == cpd.charge && counts(formula) == elements)
+    {
+        return Some(name.to_string());
+    }
+    match elements.first() {
+        Some((symbol, 1)) if elements.len() == 1 && cpd.charge > 0 => {
+            let name = element_name(symbol)?;
+            if !is_metal(symbol) || fixed_state(symbol).is_some() {
+                return Some(name.to_string());
+            }
+            let numeral = ROMAN.get(cpd.charge as usize - 1)?;
+            Some(format!("{}({})", name, numeral.to_uppercase()))
+        }
+        // The mercury(I) ion is a pair of mercury atoms.
+        Some((symbol, 2)) if elements.len() == 1 && symbol == "Hg" && cpd.charge == 2 => {
+            Some("mercury(I)".to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Names an element written in its usual form (Fe, O2, P4, S8), or a single atom of one that
+/// is not usually monatomic, e.g. atomic oxygen. Other forms such as O4 are left unnamed.
+fn element_form(elements: &IndexMap<String, usize>) -> Option<String> {
+    let (symbol, count) = elements.first()?;
+    let name = element_name(symbol)?;
+    match counts(&elemental(symbol)).get(symbol)? {
+        usual if usual == count => Some(name.to_string()),
+        _ if *count == 1 => Some(format!("atomic {}", name)),
+        _ => None,
+    }
+}
+
+/// A salt or acid split into its ions, e.g. Al2(SO4)3 is 2 Al³⁺ and 3 SO4²⁻.
+#[derive(Debug, Clone)]
+pub(crate) struct Salt {
+    pub(crate) cation: String,
+    pub(crate) cation_charge: isize,
+    pub(crate) cations: usize,
+    pub(crate) anion: &'static str,
+    pub(crate) anion_name: &'static str,
+    pub(crate) anion_charge: isize,
+    pub(crate) anions: usize,
+}
+
+/// Splits a salt of a metal or polyatomic cation into its ions, working out the charge of
+/// metals that form more than one cation from the anions.
+pub(crate) fn salt(elements: &IndexMap<String, usize>) -> Option<Salt> {
+    let mut cations = elements
+        .keys()
+        .filter(|e| is_metal(e))
+        .map(|e| (e.clone(), fixed_state(e)))
+        .collect::<Vec<_>>();
+    cations.extend(
+        POLYATOMIC_CATIONS
+            .iter()
+            .map(|(_, formula, charge)| (formula.to_string(), Some(*charge))),
+    );
+    for (cation, charge) in cations {
+        let cation_counts = counts(&cation);
+        for (anion_name, anion, anion_charge) in ANIONS {
+            let anion_counts = counts(anion);
+            for x in 1..=12 {
+                for y in 1..=12 {
+                    if !combines(elements, (&cation_counts, x), (&anion_counts, y)) {
+                        continue;
+                    }
+                    let negative = y as isize * -anion_charge;
+                    let cation_charge = match charge {
+                        Some(charge) if charge * x as isize == negative => charge,
+                        None if negative % x as isize == 0 => negative / x as isize,
+                        _ => continue,
+                    };
+                    return Some(Salt {
+                        cation,
+                        cation_charge,
+                        cations: x,
+                        anion,
+                        anion_name,
+                        anion_charge,
+                        anions: y,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Splits an acid into H⁺ and its anion. Only oxyacids and the binary acids of the halogens,
+/// sulfur and cyanide count, so water is not hydrogen hydroxide.
+pub(crate) fn acid(elements: &IndexMap<String, usize>) -> Option<Salt> {
+    let hydrogen = counts("H");
+    ANIONS.iter().find_map(|(anion_name, anion, anion_charge)| {
+        let binary = matches!(
+            *anion_name,
+            "fluoride" | "chloride" | "bromide" | "iodide" | "sulfide" | "cyanide"
+        );
+        let oxyacid = anion_name.ends_with("ate") || anion_name.ends_with("ite");
+        let count = -anion_charge as usize;
+        ((binary || oxyacid) && combines(elements, (&hydrogen, count), (&counts(anion), 1))).then(
+            || Salt {
+                cation: "H".to_string(),
+                cation_charge: 1,
+                cations: count,
+                anion,
+                anion_name,
+                anion_charge: *anion_charge,
+                anions: 1,
+            },
+        )
+    })
+}
+
+/// Acids are named from their anion: sulfate gives sulfuric acid, nitrite nitrous acid and
+/// chloride hydrochloric acid. Binary acids are only called acids in solution, so HCl(g) is
+/// hydrogen chloride.
+fn acid_name(cpd: &Compound, elements: &IndexMap<String, usize>) -> Option<String> {
+    let name = acid(elements)?.anion_name;
+    let stem = |stem: &str| match stem {
+        "sulf" => "sulfur".to_string(),
+        "phosph" => "phosphor".to_string(),
+        stem => stem.to_string(),
+    };
+    Some(if let Some(root) = name.strip_suffix("ate") {
+        format!("{}ic acid", stem(root))
+    } else if let Some(root) = name.strip_suffix("ite") {
+        format!("{}ous acid", stem(root))
+    } else {
+        match cpd.phase {
+            Some(Phase::Aqueous) => format!("hydro{}ic acid", stem(name.strip_suffix("ide")?)),
+            _ => format!("hydrogen {}", name),
+        }
+    })
+}
+
+/// Salts of a metal or polyatomic cation with any anion in the table.
+fn salt_name(elements: &IndexMap<String, usize>) -> Option<String> {
+    let salt = salt(elements)?;
+    let polyatomic = POLYATOMIC_CATIONS
+        .iter()
+        .find(|(_, formula, _)| *formula == salt.cation);
+    let cation = match polyatomic {
+        Some((name, _, _)) => name.to_string(),
+        None if fixed_state(&salt.cation).is_some() => element_name(&salt.cation)?.to_string(),
+        None => {
+            let numeral = ROMAN.get(salt.cation_charge as usize - 1)?;
+            format!(
+                "{}({})",
+                element_name(&salt.cation)?,
+                numeral.to_uppercase()
+            )
+        }
+    };
+    Some(format!("{} {}", cation, salt.anion_name))
+}
+
+/// Binary compounds of two nonmetals other than hydrogen, named with Greek prefixes in the
+/// order written, e.g. dinitrogen tetroxide.
+fn molecular_name(cpd: &Compound, elements: &IndexMap<String, usize>) -> Option<String> {
+    let written = cpd.units[0].elements().collect::<Vec<_>>();
+    let [(first, _), (second, _)] = written[..] else {
+        return None;
+    };
+    if is_metal(first) || is_metal(second) || first == "H" || second == "H" {
+        return None;
+    }
+    let (anion, _, _) = ANIONS.iter().find(|(_, formula, _)| *formula == second)?;
+    let first_name = element_name(first)?;
+    let n = elements[first];
+    let m = elements[second];
+    if n > PREFIXES.len() || m > PREFIXES.len() {
+        return None;
+    }
+    let first_name = match n {
+        1 => first_name.to_string(),
+        n => numbered(n, first_name),
+    };
+    Some(format!("{} {}", first_name, numbered(m, anion)))
+}
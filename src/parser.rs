@@ -1,24 +1,113 @@
-use crate::stoichiometry::str_to_molar_mass;
+use crate::isotopes;
+use crate::names;
 use indexmap::IndexMap;
+use num::rational::Ratio;
+use num::{CheckedAdd, CheckedMul, One, ToPrimitive, Zero};
 use std::fmt::{Display, Formatter, Write};
 
 #[derive(Debug, Clone)]
 enum Token {
+    Coefficient(Ratio<usize>),
     Element(String),
-    Subscript(usize),
-    Equals,
+    Group(&'static str),
+    Subscript(Ratio<usize>),
+    Dot,
+    Multiplier(usize),
+    Charge(isize),
+    Phase(Phase),
+    Arrow(Arrow),
     Plus,
+    Open,
+    Close(usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum LexToken {
     Upper(char),
     Lower(char),
     Number(usize),
+    Decimal(Ratio<usize>),
+    Isotope(String),
+    Charge(isize),
+    Phase(Phase),
+    Dot,
+    Slash,
     Plus,
+    Arrow(Arrow),
+    Open,
+    Close,
+}
+
+/// The arrow separating reactants from products. Resonance arrows (↔) are not reaction
+/// arrows and are rejected by the lexer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Arrow {
     Equals,
+    Forward,
+    Reversible,
+}
+
+impl Arrow {
+    pub(crate) fn plain(&self) -> &'static str {
+        match self {
+            Arrow::Equals => "=",
+            Arrow::Forward => "->",
+            Arrow::Reversible => "<=>",
+        }
+    }
+    pub(crate) fn unicode(&self) -> &'static str {
+        match self {
+            Arrow::Equals => "=",
+            Arrow::Forward => "→",
+            Arrow::Reversible => "⇌",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Phase {
+    Solid,
+    Liquid,
+    Gas,
+    Aqueous,
 }
 
+impl Phase {
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        match symbol {
+            "s" => Some(Phase::Solid),
+            "l" => Some(Phase::Liquid),
+            "g" => Some(Phase::Gas),
+            "aq" => Some(Phase::Aqueous),
+            _ => None,
+        }
+    }
+    pub(crate) fn symbol(&self) -> &'static str {
+        match self {
+            Phase::Solid => "s",
+            Phase::Liquid => "l",
+            Phase::Gas => "g",
+            Phase::Aqueous => "aq",
+        }
+    }
+}
+
+/// A piece of a typeset compound. Renderers (plain text, Unicode, the GUI) decide how each
+/// piece is drawn; the order of the pieces is the order they are written in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Coefficient(String),
+    Symbol(String),
+    /// An isotope's mass number and element symbol, e.g. 13 and C.
+    Isotope(String, String),
+    Subscript(String),
+    Dot,
+    Multiplier(String),
+    Charge(String),
+    Phase(&'static str),
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone)]
 pub(crate) enum Side {
     LHS,
@@ -29,10 +118,11 @@ pub(crate) enum Side {
 pub(crate) struct ChemicalEquation {
     pub(crate) terms: Vec<Compound>,
     pub(crate) rhs_ix: usize,
+    pub(crate) arrow: Arrow,
 }
 
 impl ChemicalEquation {
-    fn new(terms: Vec<Compound>) -> Self {
+    fn new(terms: Vec<Compound>, arrow: Arrow) -> Self {
         let mut rhs_ix = terms.len();
         for (i, cpd) in terms.iter().enumerate() {
             if let Side::RHS = cpd.side {
@@ -40,14 +130,64 @@ impl ChemicalEquation {
                 break;
             }
         }
-        ChemicalEquation { terms, rhs_ix }
+        ChemicalEquation {
+            terms,
+            rhs_ix,
+            arrow,
+        }
     }
     pub(crate) fn empty() -> Self {
         ChemicalEquation {
             terms: Vec::new(),
             rhs_ix: 0,
+            arrow: Arrow::Equals,
         }
     }
+    /// The equation with its coefficients written as decimals, e.g. `H2 + 0.5 O2 = H2O`.
+    pub(crate) fn decimal(&self, unicode: bool) -> String {
+        let term = |cpd: &Compound| {
+            let mut formula = cpd.clone();
+            formula.coefficient = Ratio::one();
+            let formula = if unicode {
+                formula.unicode()
+            } else {
+                formula.to_string()
+            };
+            match cpd.coefficient {
+                c if c.is_one() => formula,
+                c if c.is_integer() => format!("{}{}", c, formula),
+                c => format!("{} {}", decimal(c), formula),
+            }
+        };
+        let side = |terms: &[Compound]| terms.iter().map(term).collect::<Vec<_>>().join(" + ");
+        let arrow = if unicode {
+            self.arrow.unicode()
+        } else {
+            self.arrow.plain()
+        };
+        format!(
+            "{} {} {}",
+            side(&self.terms[0..self.rhs_ix]),
+            arrow,
+            side(&self.terms[self.rhs_ix..self.terms.len()])
+        )
+    }
+    /// The equation written with Unicode subscripts, superscripts and arrow.
+    pub(crate) fn unicode(&self) -> String {
+        let side = |terms: &[Compound]| {
+            terms
+                .iter()
+                .map(Compound::unicode)
+                .collect::<Vec<_>>()
+                .join(" + ")
+        };
+        format!(
+            "{} {} {}",
+            side(&self.terms[0..self.rhs_ix]),
+            self.arrow.unicode(),
+            side(&self.terms[self.rhs_ix..self.terms.len()])
+        )
+    }
 }
 
 impl Display for ChemicalEquation {
@@ -55,8 +195,8 @@ impl Display for ChemicalEquation {
         let mut iter = self.terms[0..self.rhs_ix].iter().peekable();
         while let Some(cpd) = iter.next() {
             write!(f, "{}", cpd)?;
-            if let None = iter.peek() {
-                write!(f, " = ")?;
+            if iter.peek().is_none() {
+                write!(f, " {} ", self.arrow.plain())?;
                 break;
             }
             write!(f, " + ")?;
@@ -64,7 +204,7 @@ impl Display for ChemicalEquation {
         let mut iter = self.terms[self.rhs_ix..self.terms.len()].iter().peekable();
         while let Some(cpd) = iter.next() {
             write!(f, "{}", cpd)?;
-            if let Some(_) = iter.peek() {
+            if iter.peek().is_some() {
                 write!(f, " + ")?;
             }
         }
@@ -72,96 +212,612 @@ impl Display for ChemicalEquation {
     }
 }
 
+/// Writes a number exactly as a decimal, e.g. 19/20 as 0.95, or as a fraction such as 1/3
+/// when it has no terminating decimal.
+pub(crate) fn decimal(value: Ratio<usize>) -> String {
+    let (numer, denom) = (*value.numer(), *value.denom());
+    if denom == 1 {
+        return numer.to_string();
+    }
+    let mut rest = denom;
+    for factor in [2, 5] {
+        while rest % factor == 0 {
+            rest /= factor;
+        }
+    }
+    if rest != 1 {
+        return value.to_string();
+    }
+    // Long division terminates because the denominator only has factors of 2 and 5.
+    let mut decimal = format!("{}.", numer / denom);
+    let mut remainder = (numer % denom) as u128;
+    while remainder != 0 {
+        remainder *= 10;
+        decimal.push(char::from_digit((remainder / denom as u128) as u32, 10).unwrap());
+        remainder %= denom as u128;
+    }
+    decimal
+}
+
+/// A count or coefficient as a signed ratio, for sums with charges or across both sides.
+/// Parsing rejects counts and coefficients that do not fit, see `fits_signed`.
+pub(crate) fn to_signed(value: Ratio<usize>) -> Ratio<isize> {
+    let signed = |n: usize| isize::try_from(n).expect("counts are checked when parsed");
+    Ratio::new(signed(*value.numer()), signed(*value.denom()))
+}
+
+/// Whether `value` can be converted with `to_signed`.
+fn fits_signed(value: Ratio<usize>) -> bool {
+    isize::try_from(*value.numer()).is_ok() && isize::try_from(*value.denom()).is_ok()
+}
+
+/// Organic abbreviations and the elements they stand for, in the order they are written.
+const GROUPS: [(&str, &[(&str, usize)]); 13] = [
+    ("Me", &[("C", 1), ("H", 3)]),
+    ("Et", &[("C", 2), ("H", 5)]),
+    ("Pr", &[("C", 3), ("H", 7)]),
+    ("nPr", &[("C", 3), ("H", 7)]),
+    ("iPr", &[("C", 3), ("H", 7)]),
+    ("Bu", &[("C", 4), ("H", 9)]),
+    ("nBu", &[("C", 4), ("H", 9)]),
+    ("tBu", &[("C", 4), ("H", 9)]),
+    ("Ph", &[("C", 6), ("H", 5)]),
+    ("Bn", &[("C", 7), ("H", 7)]),
+    ("Ac", &[("C", 2), ("H", 3), ("O", 1)]),
+    ("OAc", &[("O", 1), ("C", 2), ("H", 3), ("O", 1)]),
+    ("Ts", &[("C", 7), ("H", 7), ("S", 1), ("O", 2)]),
+];
+
+/// The table entry for an organic abbreviation such as Me or tBu.
+fn group(name: &str) -> Option<(&'static str, &'static [(&'static str, usize)])> {
+    GROUPS.iter().find(|(group, _)| *group == name).copied()
+}
+
+/// A piece of a formula unit as it was written.
+#[derive(Debug, Clone)]
+pub(crate) enum Part {
+    Element(String, Ratio<usize>),
+    /// An organic abbreviation such as Me or OAc and its subscript.
+    Group(&'static str, Ratio<usize>),
+    Open,
+    /// The end of a parenthesised group and how many times the group repeats.
+    Close(usize),
+}
+
+/// A dot-separated part of a formula as it was written, e.g. the `5H2O` in `CuSO4·5H2O`.
+#[derive(Debug, Clone)]
+pub(crate) struct FormulaUnit {
+    pub(crate) count: usize,
+    pub(crate) parts: Vec<Part>,
+}
+
+impl FormulaUnit {
+    /// Each element as written with its own subscript, ignoring any group it is in, so
+    /// Ca(OH)2 gives Ca 1, O 1, H 1. Abbreviations are expanded, so MeOH gives C 1, H 3, O 1, H 1.
+    pub(crate) fn elements(&self) -> impl Iterator<Item = (&str, Ratio<usize>)> {
+        self.parts.iter().flat_map(|part| match part {
+            Part::Element(elem, sub) => vec![(elem.as_str(), *sub)],
+            Part::Group(name, sub) => group(name)
+                .unwrap()
+                .1
+                .iter()
+                .map(|(elem, count)| (*elem, sub * count))
+                .collect(),
+            _ => Vec::new(),
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Compound {
-    pub(crate) coefficient: usize,
-    pub(crate) elements: IndexMap<String, usize>,
+    pub(crate) coefficient: Ratio<usize>,
+    pub(crate) elements: IndexMap<String, Ratio<usize>>,
+    pub(crate) units: Vec<FormulaUnit>,
+    pub(crate) charge: isize,
+    pub(crate) phase: Option<Phase>,
     pub(crate) side: Side,
     pub(crate) molar_mass: f32,
+    /// How symbols that are both an element and an abbreviation, such as Pr, were read.
+    pub(crate) notes: Vec<String>,
 }
 
 impl Compound {
-    fn new(elements_: &[Token], side: Side) -> Self {
+    fn new(elements_: &[Token], side: Side) -> Result<Self, String> {
         let mut molar_mass = 0.0;
         let mut elements = IndexMap::new();
-        let mut iter = elements_.into_iter().peekable();
-        while let Some(token) = iter.next() {
-            if let Token::Element(elem) = token {
-                if let Some(Token::Subscript(sub)) = iter.peek() {
-                    elements.insert(elem.clone(), *sub);
-                    molar_mass += str_to_molar_mass(&elem) * *sub as f32;
-                    iter.next();
-                } else {
-                    elements.insert(elem.clone(), 1);
-                    molar_mass += str_to_molar_mass(&elem)
+        let mut units = vec![FormulaUnit {
+            count: 1,
+            parts: Vec::new(),
+        }];
+        let mut coefficient = Ratio::from_integer(1);
+        let mut charge = 0;
+        let mut phase = None;
+        // How many times each token repeats because of the groups it is in, found by walking
+        // backwards so each group's count is known before its contents.
+        let mut repeats = vec![1; elements_.len()];
+        let mut groups = Vec::new();
+        for (i, token) in elements_.iter().enumerate().rev() {
+            match token {
+                Token::Close(count) => groups.push(*count),
+                Token::Open => {
+                    groups.pop();
+                }
+                _ => {
+                    repeats[i] = groups
+                        .iter()
+                        .try_fold(1usize, |product, count| product.checked_mul(*count))
+                        .ok_or("a group repeats too many times")?
+                }
+            }
+        }
+        let mut iter = elements_.iter().enumerate().peekable();
+        while let Some((i, token)) = iter.next() {
+            match token {
+                Token::Coefficient(c) if !fits_signed(*c) => {
+                    return Err(format!("the coefficient {} is too large", c))
+                }
+                Token::Coefficient(c) => coefficient = *c,
+                Token::Element(_) | Token::Group(_) => {
+                    let sub = match iter.peek() {
+                        Some((_, Token::Subscript(sub))) => {
+                            iter.next();
+                            *sub
+                        }
+                        _ => Ratio::one(),
+                    };
+                    let unit = units.last_mut().unwrap();
+                    let (part, atoms) = match token {
+                        Token::Element(elem) => {
+                            (Part::Element(elem.clone(), sub), vec![(elem.as_str(), 1)])
+                        }
+                        Token::Group(name) => {
+                            (Part::Group(name, sub), group(name).unwrap().1.to_vec())
+                        }
+                        _ => unreachable!(),
+                    };
+                    for (elem, n) in atoms {
+                        let too_large = || format!("the number of {} atoms is too large", elem);
+                        let count = [n, repeats[i], unit.count]
+                            .iter()
+                            .try_fold(sub, |count, n| count.checked_mul(&Ratio::from_integer(*n)))
+                            .ok_or_else(too_large)?;
+                        let total = elements
+                            .get(elem)
+                            .map_or(Some(count), |total: &Ratio<usize>| {
+                                total.checked_add(&count)
+                            })
+                            .filter(|total| fits_signed(*total))
+                            .ok_or_else(too_large)?;
+                        elements.insert(elem.to_string(), total);
+                        molar_mass += isotopes::molar_mass(elem).unwrap() * count.to_f32().unwrap();
+                    }
+                    unit.parts.push(part);
+                }
+                Token::Open => units.last_mut().unwrap().parts.push(Part::Open),
+                Token::Close(count) => units.last_mut().unwrap().parts.push(Part::Close(*count)),
+                Token::Dot => {
+                    let count = match iter.peek() {
+                        Some((_, Token::Multiplier(count))) => {
+                            iter.next();
+                            *count
+                        }
+                        _ => 1,
+                    };
+                    units.push(FormulaUnit {
+                        count,
+                        parts: Vec::new(),
+                    });
                 }
+                Token::Charge(c) => charge = *c,
+                Token::Phase(p) => phase = Some(*p),
+                _ => (),
             }
         }
-        Self {
-            coefficient: 1,
+        Ok(Self {
+            coefficient,
             elements,
+            units,
+            charge,
+            phase,
             side,
             molar_mass,
+            notes: Vec::new(),
+        })
+    }
+    /// The number of atoms of each element, if every one is a whole number (it is not for
+    /// non-stoichiometric compounds such as Fe0.95O).
+    pub(crate) fn counts(&self) -> Option<IndexMap<String, usize>> {
+        self.elements
+            .iter()
+            .map(|(elem, count)| {
+                count
+                    .is_integer()
+                    .then(|| (elem.clone(), count.to_integer()))
+            })
+            .collect()
+    }
+    pub(crate) fn segments(&self) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        if !self.coefficient.is_integer() {
+            segments.push(Segment::Coefficient(format!("{} ", self.coefficient)));
+        } else if self.coefficient != Ratio::from_integer(1) {
+            segments.push(Segment::Coefficient(self.coefficient.to_string()));
         }
+        for (i, unit) in self.units.iter().enumerate() {
+            if i != 0 {
+                segments.push(Segment::Dot);
+                if unit.count != 1 {
+                    segments.push(Segment::Multiplier(unit.count.to_string()));
+                }
+            }
+            for part in &unit.parts {
+                let sub = match part {
+                    Part::Element(elem, sub) => {
+                        segments.push(match isotopes::split(elem) {
+                            Some((symbol, mass_number)) if elem.starts_with('[') => {
+                                Segment::Isotope(mass_number.to_string(), symbol.to_string())
+                            }
+                            _ => Segment::Symbol(elem.clone()),
+                        });
+                        sub
+                    }
+                    Part::Group(name, sub) => {
+                        segments.push(Segment::Symbol(name.to_string()));
+                        sub
+                    }
+                    Part::Open => {
+                        segments.push(Segment::Symbol("(".to_string()));
+                        continue;
+                    }
+                    Part::Close(count) => {
+                        segments.push(Segment::Symbol(")".to_string()));
+                        if *count != 1 {
+                            segments.push(Segment::Subscript(count.to_string()));
+                        }
+                        continue;
+                    }
+                };
+                if !sub.is_one() {
+                    segments.push(Segment::Subscript(decimal(*sub)));
+                }
+            }
+        }
+        if self.charge != 0 {
+            let sign = if self.charge > 0 { '+' } else { '-' };
+            segments.push(Segment::Charge(match self.charge.abs() {
+                1 => sign.to_string(),
+                n => format!("{}{}", n, sign),
+            }));
+        }
+        if let Some(phase) = self.phase {
+            segments.push(Segment::Phase(phase.symbol()));
+        }
+        segments
     }
+    /// The compound without its coefficient, e.g. `SO4^2-(aq)`.
     pub(crate) fn raw(&self) -> String {
         let mut f = String::new();
-        for (elem, i) in &self.elements {
-            match i {
-                1 => write!(f, "{}", elem).unwrap(),
-                _ => write!(f, "{}{}", elem, i).unwrap(),
+        for segment in self.segments() {
+            if let Segment::Coefficient(_) = segment {
+                continue;
+            }
+            write_plain(&mut f, &segment).unwrap();
+        }
+        f
+    }
+    /// The compound written with Unicode subscript and superscript characters, suitable for
+    /// pasting into documents, e.g. `2SO₄²⁻(aq)`.
+    pub(crate) fn unicode(&self) -> String {
+        let mut f = String::new();
+        for segment in self.segments() {
+            match segment {
+                Segment::Subscript(sub) => f.extend(sub.chars().map(to_subscript)),
+                Segment::Charge(charge) => f.extend(charge.chars().map(to_superscript)),
+                Segment::Isotope(mass_number, symbol) => {
+                    f.extend(mass_number.chars().map(to_superscript));
+                    f.push_str(&symbol);
+                }
+                Segment::Dot => f.push('·'),
+                segment => write_plain(&mut f, &segment).unwrap(),
             }
         }
         f
     }
 }
 
+fn write_plain(f: &mut impl Write, segment: &Segment) -> std::fmt::Result {
+    match segment {
+        Segment::Coefficient(s)
+        | Segment::Symbol(s)
+        | Segment::Subscript(s)
+        | Segment::Multiplier(s) => write!(f, "{}", s),
+        Segment::Isotope(mass_number, symbol) => write!(f, "[{}{}]", mass_number, symbol),
+        Segment::Dot => write!(f, "*"),
+        Segment::Charge(charge) => write!(f, "^{}", charge),
+        Segment::Phase(phase) => write!(f, "({})", phase),
+    }
+}
+
+fn to_subscript(c: char) -> char {
+    match c {
+        '0'..='9' => char::from_u32('₀' as u32 + c.to_digit(10).unwrap()).unwrap(),
+        other => other,
+    }
+}
+
+fn to_superscript(c: char) -> char {
+    match c {
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '0' | '4'..='9' => char::from_u32('⁰' as u32 + c.to_digit(10).unwrap()).unwrap(),
+        '+' => '⁺',
+        '-' => '⁻',
+        other => other,
+    }
+}
+
 impl Display for Compound {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.coefficient != 1 {
-            write!(f, "{}", self.coefficient)?;
+        for segment in self.segments() {
+            write_plain(f, &segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrites text pasted from textbooks and web pages into the plain syntax the lexer expects:
+/// Unicode subscripts become digits, vulgar fractions (½) become `1/2`, runs of superscripts become `^` charges, Unicode arrows
+/// (and dashes typed before `>`) become their ASCII spellings and any kind of whitespace becomes a space.
+fn normalize(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '₀'..='₉' => result.push(char::from_digit(char as u32 - '₀' as u32, 10).unwrap()),
+            c if from_superscript(c).is_some() => {
+                result.push('^');
+                result.push(from_superscript(c).unwrap());
+                while let Some(c) = chars.peek().and_then(|c| from_superscript(*c)) {
+                    result.push(c);
+                    chars.next();
+                }
+            }
+            '½' => result.push_str(" 1/2 "),
+            '¼' => result.push_str(" 1/4 "),
+            '¾' => result.push_str(" 3/4 "),
+            '→' | '⟶' | '⇒' | '⟹' | '➔' | '➜' => result.push_str("->"),
+            // Word processors turn the hyphen in -> into an en or em dash.
+            '–' | '—' if chars.peek() == Some(&'>') => {
+                chars.next();
+                result.push_str("->");
+            }
+            '⇌' | '⇄' | '⇋' => result.push_str("<=>"),
+            '−' => result.push('-'),
+            c if c.is_whitespace() => result.push(' '),
+            c => result.push(c),
         }
-        for (elem, i) in &self.elements {
-            match i {
-                1 => write!(f, "{}", elem)?,
-                _ => write!(f, "{}{}", elem, i)?,
+    }
+    result
+}
+
+/// Rejects arrows `normalize` has no ASCII spelling for, before they can be mistaken for part
+/// of a compound name.
+fn check_arrows(input: &str) -> Result<(), String> {
+    let arrow = input.chars().find(|c| {
+        matches!(*c as u32, 0x2190..=0x21FF | 0x27F0..=0x27FF | 0x2900..=0x297F | 0x2794..=0x27BF)
+    });
+    match arrow {
+        Some(arrow @ ('↔' | '⟷' | '⇔' | '⟺')) => Err(format!(
+            "'{}' is a resonance arrow, not a reaction arrow; use '->' or '<=>'",
+            arrow
+        )),
+        Some(arrow) => Err(format!("unrecognized arrow '{}'", arrow)),
+        None => Ok(()),
+    }
+}
+
+fn from_superscript(c: char) -> Option<char> {
+    match c {
+        '⁰' => Some('0'),
+        '¹' => Some('1'),
+        '²' => Some('2'),
+        '³' => Some('3'),
+        '⁴'..='⁹' => char::from_digit(c as u32 - '⁰' as u32, 10),
+        '⁺' => Some('+'),
+        '⁻' => Some('-'),
+        _ => None,
+    }
+}
+
+/// Replaces any terms of `input` written as names, such as `2 sodium chloride(aq)`, with
+/// their formulas. A term is taken to be a name if it contains a space or two lower case
+/// letters in a row, which no formula does.
+fn resolve_names(input: &str) -> Result<String, String> {
+    let mut result = String::with_capacity(input.len());
+    let mut term = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        let charge = term
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .ends_with('^');
+        let separator = match char {
+            '+' if !charge => "+".to_string(),
+            '=' if chars.peek() == Some(&'>') => {
+                chars.next();
+                "=>".to_string()
+            }
+            '=' => "=".to_string(),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                "->".to_string()
             }
+            '<' => {
+                let mut arrow = "<".to_string();
+                arrow.extend(chars.next_if(|c| *c == '=' || *c == '-'));
+                arrow.extend(chars.next_if(|c| *c == '>'));
+                arrow
+            }
+            c => {
+                term.push(c);
+                continue;
+            }
+        };
+        result.push_str(&resolve_term(&term)?);
+        result.push_str(&separator);
+        term.clear();
+    }
+    result.push_str(&resolve_term(&term)?);
+    Ok(result)
+}
+
+fn resolve_term(term: &str) -> Result<String, String> {
+    let term = term.trim();
+    let name_start = term
+        .find(|c: char| !c.is_ascii_digit() && !c.is_whitespace() && !matches!(c, '/' | '.'))
+        .unwrap_or(term.len());
+    let (coefficient, name) = term.split_at(name_start);
+    let (name, phase) = match name.rfind('(') {
+        Some(i) if Phase::from_symbol(name[i..].trim_matches(['(', ')'])).is_some() => {
+            name.split_at(i)
         }
-        Ok(())
+        _ => (name, ""),
+    };
+    let error = match lex_formula(term).and_then(parse_tokens) {
+        Ok(_) => return Ok(format!(" {} ", term)),
+        Err(error) => error,
+    };
+    // Stray operators mean the equation is malformed, not that the term is a name.
+    if name.contains(['=', '<', '>', '-']) {
+        return Err(error);
+    }
+    let letters = name.chars().collect::<Vec<_>>();
+    let looks_like_name = name.trim().contains(' ')
+        || letters
+            .windows(2)
+            .any(|w| w[0].is_lowercase() && w[1].is_lowercase());
+    match names::formula_from_name(name) {
+        Ok(formula) => Ok(format!(" {}{}{} ", coefficient.trim(), formula, phase)),
+        Err(name_error) if looks_like_name => Err(name_error),
+        Err(_) => Err(error),
     }
 }
 
 fn lex(input: &str) -> Result<Vec<LexToken>, String> {
-    let mut result = Vec::new();
+    let input = normalize(input);
+    check_arrows(&input)?;
+    lex_formula(&resolve_names(&input)?)
+}
 
+/// Splits normalised input, with any names already replaced by formulas, into tokens.
+fn lex_formula(input: &str) -> Result<Vec<LexToken>, String> {
+    let mut result = Vec::new();
     let mut rename = input.chars().peekable();
     while let Some(char) = rename.next() {
         match char {
             'A'..='Z' => result.push(LexToken::Upper(char)),
             'a'..='z' => result.push(LexToken::Lower(char)),
-            '0'..='9' => match rename.peek() {
-                Some('0'..='9') => {
-                    let mut num_str = String::new();
-                    num_str.push(char);
-                    num_str.push(rename.next().unwrap());
-                    result.push(LexToken::Number(
-                        num_str
-                            .parse::<usize>()
-                            .map_err(|_| "error parsing number to string".to_string())?,
-                    ))
-                }
-                _ => result.push(LexToken::Number(
-                    char.to_string()
+            '0'..='9' => {
+                let mut digits = char.to_string();
+                while let Some(digit) = rename.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                let too_large = |digits: &str| format!("the number {} is too large", digits);
+                let number = digits.parse::<usize>().map_err(|_| too_large(&digits))?;
+                // A decimal point only counts if a digit follows it, e.g. the 0.95 in Fe0.95O.
+                if rename.peek() == Some(&'.')
+                    && rename.clone().nth(1).is_some_and(|c| c.is_ascii_digit())
+                {
+                    rename.next();
+                    let mut places = String::new();
+                    while let Some(digit) = rename.next_if(char::is_ascii_digit) {
+                        places.push(digit);
+                    }
+                    let decimal = format!("{}.{}", digits, places);
+                    let denom = u32::try_from(places.len())
+                        .ok()
+                        .and_then(|places| 10usize.checked_pow(places))
+                        .ok_or_else(|| too_large(&decimal))?;
+                    let numer = format!("{}{}", digits, places)
                         .parse::<usize>()
-                        .map_err(|_| "error parsing number to string".to_string())?,
-                )),
-            },
+                        .map_err(|_| too_large(&decimal))?;
+                    result.push(LexToken::Decimal(Ratio::new(numer, denom)));
+                } else {
+                    result.push(LexToken::Number(number));
+                }
+            }
+            '[' => {
+                let label = rename
+                    .by_ref()
+                    .take_while(|c| *c != ']')
+                    .collect::<String>();
+                let symbol = label
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(label.len());
+                let (mass_number, element) = label.split_at(symbol);
+                let mass_number = mass_number
+                    .parse()
+                    .map_err(|_| format!("expected a mass number in '[{}]'", label))?;
+                result.push(LexToken::Isotope(isotopes::label(element, mass_number)?));
+            }
+            '^' => {
+                let mut digits = String::new();
+                while let Some(digit) = rename.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                let too_large = || format!("the number {} is too large", digits);
+                let magnitude = match digits.as_str() {
+                    "" => 0,
+                    digits => digits.parse::<usize>().map_err(|_| too_large())?,
+                };
+                // A mass number rather than a charge, as in ^13C or ^2H.
+                if let Some(upper) = rename.next_if(char::is_ascii_uppercase) {
+                    let mut element = upper.to_string();
+                    element.extend(rename.next_if(char::is_ascii_lowercase));
+                    if magnitude == 0 {
+                        return Err(format!("expected a mass number before {}", element));
+                    }
+                    result.push(LexToken::Isotope(isotopes::label(&element, magnitude)?));
+                    continue;
+                }
+                let magnitude = isize::try_from(magnitude.max(1)).map_err(|_| too_large())?;
+                match rename.next() {
+                    Some('+') => result.push(LexToken::Charge(magnitude)),
+                    Some('-') => result.push(LexToken::Charge(-magnitude)),
+                    _ => return Err("expected '+' or '-' after '^' in charge".to_string()),
+                }
+            }
+            '(' => {
+                let symbol = rename.clone().take_while(|c| *c != ')').collect::<String>();
+                match Phase::from_symbol(&symbol) {
+                    Some(phase) => {
+                        rename.nth(symbol.chars().count());
+                        result.push(LexToken::Phase(phase));
+                    }
+                    None => result.push(LexToken::Open),
+                }
+            }
+            ')' => result.push(LexToken::Close),
+            '·' | '•' | '*' => result.push(LexToken::Dot),
+            '/' => result.push(LexToken::Slash),
             '+' => {
                 result.push(LexToken::Plus);
             }
-            '=' | '→' => {
-                result.push(LexToken::Equals);
+            '=' => match rename.peek() {
+                Some('>') => {
+                    rename.next();
+                    result.push(LexToken::Arrow(Arrow::Forward));
+                }
+                _ => result.push(LexToken::Arrow(Arrow::Equals)),
+            },
+            '-' if rename.peek() == Some(&'>') => {
+                rename.next();
+                result.push(LexToken::Arrow(Arrow::Forward));
             }
+            '<' => match (rename.next(), rename.next()) {
+                (Some('=' | '-'), Some('>')) => result.push(LexToken::Arrow(Arrow::Reversible)),
+                _ => return Err("expected '<=>' or '<->' during lexing".to_string()),
+            },
             '\n' | '\r' => (),
             ' ' => (),
             other => return Err(format!("unrecognized symbol '{}' during lexing", other)),
@@ -171,46 +827,261 @@ fn lex(input: &str) -> Result<Vec<LexToken>, String> {
 }
 
 pub(crate) fn parse(input: &str) -> Result<ChemicalEquation, String> {
-    let lex_stream = lex(input)?;
+    parse_tokens(lex(input)?)
+}
+
+fn parse_tokens(lex_stream: Vec<LexToken>) -> Result<ChemicalEquation, String> {
     let mut token_stream = Vec::new();
 
     let mut iter = lex_stream.into_iter().peekable();
     while let Some(token) = iter.next() {
         match token {
+            LexToken::Upper('O')
+                if matches!(
+                    (iter.clone().next(), iter.clone().nth(1)),
+                    (Some(LexToken::Upper('A')), Some(LexToken::Lower('c')))
+                ) =>
+            {
+                iter.nth(1);
+                token_stream.push(Token::Group("OAc"));
+            }
             LexToken::Upper(char) => {
                 let mut element = char.to_string();
-                if let Some(token) = iter.peek() {
-                    if let LexToken::Lower(c) = token {
-                        element.push(*c);
-                        iter.next();
-                    }
+                if let Some(LexToken::Lower(c)) = iter.peek() {
+                    element.push(*c);
+                    iter.next();
+                }
+                // Pr and Ac are also elements; `resolve_groups` decides which is meant.
+                if let Some((name, _)) = group(&element).filter(|_| !is_ambiguous(&element)) {
+                    token_stream.push(Token::Group(name));
+                    continue;
+                }
+                if isotopes::molar_mass(&element).is_none() {
+                    return Err(format!("unknown element '{}'", element));
                 }
                 token_stream.push(Token::Element(element));
             }
+            LexToken::Isotope(label) => token_stream.push(Token::Element(label)),
+            LexToken::Lower(prefix @ ('n' | 'i' | 't')) => {
+                let name = match (iter.next(), iter.next()) {
+                    (Some(LexToken::Upper(upper)), Some(LexToken::Lower(lower))) => {
+                        format!("{}{}{}", prefix, upper, lower)
+                    }
+                    _ => return Err("unexpected lower case token in parse stream".to_string()),
+                };
+                let (name, _) = group(&name).ok_or_else(|| format!("unknown group '{}'", name))?;
+                token_stream.push(Token::Group(name));
+            }
             LexToken::Lower(_) => {
                 return Err("unexpected lower case token in parse stream".to_string())
             }
-            LexToken::Number(num) => {
-                if let Some(Token::Element(_)) = token_stream.last() {
-                    token_stream.push(Token::Subscript(num));
+            LexToken::Number(num) => match token_stream.last_mut() {
+                Some(Token::Element(_) | Token::Group(_)) => {
+                    token_stream.push(Token::Subscript(Ratio::from_integer(num)))
                 }
-            }
+                Some(Token::Dot) => token_stream.push(Token::Multiplier(num)),
+                Some(Token::Close(count)) => *count = num,
+                None | Some(Token::Plus | Token::Arrow(_)) => {
+                    let denom = match iter.next_if(|t| matches!(t, LexToken::Slash)) {
+                        Some(_) => match iter.next() {
+                            Some(LexToken::Number(denom)) => denom,
+                            _ => return Err(format!("expected a number after '{}/'", num)),
+                        },
+                        None => 1,
+                    };
+                    if num == 0 {
+                        return Err("coefficients must be greater than zero".to_string());
+                    }
+                    if denom == 0 {
+                        return Err(format!("division by zero in coefficient '{}/0'", num));
+                    }
+                    token_stream.push(Token::Coefficient(Ratio::new(num, denom)));
+                }
+                _ => return Err(format!("unexpected number '{}'", num)),
+            },
+            LexToken::Decimal(num) => match token_stream.last() {
+                Some(Token::Element(_) | Token::Group(_)) if !num.is_zero() => {
+                    token_stream.push(Token::Subscript(num))
+                }
+                None | Some(Token::Plus | Token::Arrow(_)) if !num.is_zero() => {
+                    token_stream.push(Token::Coefficient(num))
+                }
+                _ => return Err(format!("unexpected number '{}'", decimal(num))),
+            },
+            LexToken::Slash => return Err("'/' is only allowed in coefficients".to_string()),
+            LexToken::Charge(charge) => token_stream.push(Token::Charge(charge)),
+            LexToken::Phase(phase) => token_stream.push(Token::Phase(phase)),
+            LexToken::Dot => token_stream.push(Token::Dot),
             LexToken::Plus => token_stream.push(Token::Plus),
-            LexToken::Equals => token_stream.push(Token::Equals),
+            LexToken::Arrow(arrow) => token_stream.push(Token::Arrow(arrow)),
+            LexToken::Open => token_stream.push(Token::Open),
+            LexToken::Close => token_stream.push(Token::Close(1)),
         }
     }
     let mut compounds = Vec::new();
     let mut last = 0;
     let mut side = Side::LHS;
+    let mut eq_arrow = Arrow::Equals;
     for (i, token) in token_stream.clone().into_iter().enumerate() {
-        if let Token::Plus | Token::Equals = token {
-            compounds.push(Compound::new(&token_stream[last..i], side.clone()));
+        if let Token::Plus | Token::Arrow(_) = token {
+            check_groups(&token_stream[last..i])?;
+            let notes = resolve_groups(&mut token_stream[last..i]);
+            let mut cpd = Compound::new(&token_stream[last..i], side.clone())?;
+            cpd.notes = notes;
+            compounds.push(cpd);
             last = i + 1;
-            if let Token::Equals = token {
+            if let Token::Arrow(arrow) = token {
                 side = Side::RHS;
+                eq_arrow = arrow;
             }
         }
     }
-    compounds.push(Compound::new(&token_stream[last..token_stream.len()], side));
-    Ok(ChemicalEquation::new(compounds))
+    check_groups(&token_stream[last..token_stream.len()])?;
+    let len = token_stream.len();
+    let notes = resolve_groups(&mut token_stream[last..len]);
+    let mut cpd = Compound::new(&token_stream[last..len], side)?;
+    cpd.notes = notes;
+    compounds.push(cpd);
+    Ok(ChemicalEquation::new(compounds, eq_arrow))
+}
+
+/// Checks that every '(' in a compound is closed within the same formula unit.
+fn check_groups(tokens: &[Token]) -> Result<(), String> {
+    let mut depth = 0;
+    for token in tokens {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close(_) if depth == 0 => return Err("unmatched ')'".to_string()),
+            Token::Close(_) => depth -= 1,
+            Token::Dot if depth > 0 => return Err("unclosed '('".to_string()),
+            _ => (),
+        }
+    }
+    match depth {
+        0 => Ok(()),
+        _ => Err("unclosed '('".to_string()),
+    }
+}
+
+/// Abbreviations that are also element symbols, with the group and element names.
+const AMBIGUOUS: [(&str, &str, &str); 3] = [
+    ("Pr", "propyl", "praseodymium"),
+    ("Ac", "acetyl", "actinium"),
+    ("Ts", "tosyl", "tennessine"),
+];
+
+/// Whether an abbreviation is also an element symbol.
+fn is_ambiguous(symbol: &str) -> bool {
+    AMBIGUOUS
+        .iter()
+        .any(|(abbreviation, _, _)| *abbreviation == symbol)
+}
+
+/// What follows an ambiguous symbol in the usual spellings of organic compounds, such as AcOH,
+/// Ac2O, PrNH2 and TsCl.
+const ORGANIC_SUFFIXES: [&str; 5] = ["OH", "NH2", "Cl", "Br", "2O"];
+
+/// Decides whether Pr, Ac and Ts mean propyl, acetyl and tosyl or praseodymium, actinium
+/// and tennessine, returning a note saying how each was read. A symbol followed by a
+/// parenthesised group, as in Pr(OAc)3 or Pr2(CO3)3, is the element. Otherwise it is the
+/// abbreviation in the usual spellings (AcOH, Ac2O, PrOH, TsCl) or in compounds that also
+/// contain carbon or another abbreviation (PrCOOH, AcOMe), and the element anywhere else
+/// (PrCl3, Ac2O3).
+fn resolve_groups(tokens: &mut [Token]) -> Vec<String> {
+    let organic = tokens.iter().any(|token| match token {
+        Token::Element(elem) => isotopes::element(elem) == "C",
+        Token::Group(_) => true,
+        _ => false,
+    });
+    let charged = tokens.iter().any(|token| matches!(token, Token::Charge(_)));
+    let formula = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token, Token::Coefficient(_) | Token::Phase(_)))
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let mut notes = Vec::new();
+    for (position, &i) in formula.iter().enumerate() {
+        let Token::Element(elem) = &tokens[i] else {
+            continue;
+        };
+        let Some(&(symbol, group_name, element_name)) =
+            AMBIGUOUS.iter().find(|(symbol, _, _)| symbol == elem)
+        else {
+            continue;
+        };
+        let mut rest = formula[position + 1..]
+            .iter()
+            .map(|i| &tokens[*i])
+            .peekable();
+        let salt = {
+            let mut after = rest.clone();
+            after.next_if(|token| matches!(token, Token::Subscript(_)));
+            matches!(after.next(), Some(Token::Open))
+        };
+        let suffix = rest
+            .by_ref()
+            .map(|token| match token {
+                Token::Element(elem) => elem.clone(),
+                Token::Subscript(sub) => decimal(*sub),
+                _ => "?".to_string(),
+            })
+            .collect::<String>();
+        let usual = position == 0 && !charged && ORGANIC_SUFFIXES.contains(&suffix.as_str());
+        if !salt && (usual || organic) {
+            let (name, formula) = group(symbol).unwrap();
+            let formula = formula
+                .iter()
+                .map(|(elem, n)| match n {
+                    1 => elem.to_string(),
+                    n => format!("{}{}", elem, n),
+                })
+                .collect::<String>();
+            tokens[i] = Token::Group(name);
+            notes.push(format!(
+                "{} read as {} ({}), not {}",
+                symbol, group_name, formula, element_name
+            ));
+        } else {
+            notes.push(format!(
+                "{} read as {}, not {}",
+                symbol, element_name, group_name
+            ));
+        }
+    }
+    notes
+}
+
+/// Parses a single compound, e.g. `Fe2O3` or `SO4^2-(aq)`.
+pub(crate) fn parse_compound(input: &str) -> Result<Compound, String> {
+    let mut eq = parse(input)?;
+    if eq.terms.len() != 1 {
+        return Err(format!("expected a single compound, got '{}'", input));
+    }
+    Ok(eq.terms.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_charges_and_mass_numbers_that_overflow() {
+        for formula in [
+            "Fe^99999999999999999999999+",
+            "Fe^9223372036854775808+",
+            "^99999999999999999999999C",
+        ] {
+            assert!(parse_compound(formula).unwrap_err().contains("too large"));
+        }
+    }
+
+    #[test]
+    fn reads_charges_and_mass_numbers() {
+        assert_eq!(parse_compound("SO4^2-").unwrap().charge, -2);
+        assert!(parse_compound("^13CH4")
+            .unwrap()
+            .elements
+            .contains_key("[13C]"));
+    }
 }
@@ -0,0 +1,315 @@
+use crate::combustion;
+use crate::names::{self, elemental, salt_formula, Salt};
+use crate::parser::{self, ChemicalEquation, Compound, Side};
+use crate::redox::{fixed_state, is_metal, typical_negative_state};
+use crate::solubility::is_soluble;
+
+/// Metals and hydrogen from most to least reactive, with the charge each takes when it
+/// displaces another. A metal displaces any below it from its compounds.
+const ACTIVITY_SERIES: [(&str, isize); 23] = [
+    ("Li", 1),
+    ("K", 1),
+    ("Ba", 2),
+    ("Sr", 2),
+    ("Ca", 2),
+    ("Na", 1),
+    ("Mg", 2),
+    ("Al", 3),
+    ("Mn", 2),
+    ("Zn", 2),
+    ("Cr", 3),
+    ("Fe", 2),
+    ("Cd", 2),
+    ("Co", 2),
+    ("Ni", 2),
+    ("Sn", 2),
+    ("Pb", 2),
+    ("H", 1),
+    ("Cu", 2),
+    ("Ag", 1),
+    ("Hg", 2),
+    ("Pt", 2),
+    ("Au", 3),
+];
+
+/// Metals reactive enough to displace hydrogen from cold water.
+const WATER_REACTIVE: usize = 6;
+
+const HALOGENS: [&str; 4] = ["F", "Cl", "Br", "I"];
+
+/// Whether `eq` was entered with reactants only, e.g. `AgNO3 + NaCl =`.
+pub(crate) fn awaits_products(eq: &ChemicalEquation) -> bool {
+    let products = &eq.terms[eq.rhs_ix..];
+    !products.is_empty() && products.iter().all(|cpd| cpd.elements.is_empty())
+}
+
+/// Fills in the products of `eq` if it was entered with reactants only, keeping the reactants
+/// as they were written. Equations that already have products, or no arrow at all, are returned
+/// unchanged.
+pub(crate) fn complete(eq: ChemicalEquation) -> Result<ChemicalEquation, String> {
+    if !awaits_products(&eq) {
+        return Ok(eq);
+    }
+    let mut terms = eq.terms[..eq.rhs_ix]
+        .iter()
+        .filter(|cpd| !cpd.elements.is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    let products = predict(&terms.iter().collect::<Vec<_>>())?;
+    let rhs_ix = terms.len();
+    for mut cpd in parser::parse(&products.join(" + "))?.terms {
+        cpd.side = Side::RHS;
+        terms.push(cpd);
+    }
+    Ok(ChemicalEquation {
+        terms,
+        rhs_ix,
+        arrow: eq.arrow,
+    })
+}
+
+/// Predicts the products of combustion, synthesis from the elements, decomposition, single
+/// displacement, neutralisation and double displacement (precipitation) reactions. Products
+/// of double displacement are given phases from the solubility rules.
+pub(crate) fn predict(reactants: &[&Compound]) -> Result<Vec<String>, String> {
+    match reactants {
+        [cpd] => decomposition(cpd),
+        [a, b] => {
+            let oxygen = |cpd: &Compound| is(cpd, "O2");
+            let free = |cpd: &Compound| cpd.charge == 0 && cpd.elements.len() == 1;
+            if oxygen(a) != oxygen(b) {
+                let fuel = if oxygen(a) { b } else { a };
+                let burns = ["C", "H", "S"]
+                    .iter()
+                    .any(|e| fuel.elements.contains_key(*e));
+                let elements = ["C", "H", "O", "N", "S"];
+                if burns && fuel.elements.keys().all(|e| elements.contains(&e.as_str())) {
+                    return Ok(combustion::combustion_products(fuel)
+                        .into_iter()
+                        .map(String::from)
+                        .collect());
+                }
+            }
+            if free(a) && free(b) {
+                return synthesis(a, b);
+            }
+            if free(a) && !free(b) {
+                return single_displacement(a, b);
+            }
+            if free(b) && !free(a) {
+                return single_displacement(b, a);
+            }
+            for (base, acid) in [(a, b), (b, a)] {
+                let acid = acid.counts().and_then(|elements| names::acid(&elements));
+                if let (true, Some(acid)) = (is(base, "NH3"), acid) {
+                    return Ok(vec![salt_formula("NH4", 1, acid.anion, acid.anion_charge)]);
+                }
+            }
+            double_displacement(a, b)
+        }
+        _ => Err("products can only be predicted for one or two reactants".to_string()),
+    }
+}
+
+fn salt(cpd: &Compound) -> Option<Salt> {
+    names::salt(&cpd.counts()?)
+}
+
+fn ions(cpd: &Compound) -> Option<Salt> {
+    let elements = cpd.counts()?;
+    names::salt(&elements).or_else(|| names::acid(&elements))
+}
+
+fn is(cpd: &Compound, formula: &str) -> bool {
+    parser::parse_compound(formula)
+        .is_ok_and(|f| f.elements == cpd.elements && f.charge == cpd.charge)
+}
+
+fn decomposition(cpd: &Compound) -> Result<Vec<String>, String> {
+    let products = |products: &[&str]| Ok(products.iter().map(|p| p.to_string()).collect());
+    for (reactant, decomposed) in [
+        ("H2O2", &["H2O", "O2"][..]),
+        ("H2CO3", &["H2O", "CO2"]),
+        ("H2SO3", &["H2O", "SO2"]),
+        ("NH4OH", &["NH3", "H2O"]),
+        ("NH4Cl", &["NH3", "HCl"]),
+    ] {
+        if is(cpd, reactant) {
+            return products(decomposed);
+        }
+    }
+    if let Some(salt) = salt(cpd) {
+        let oxide = || salt_formula(&salt.cation, salt.cation_charge, "O", -2);
+        let alkali = matches!(salt.cation.as_str(), "Li" | "Na" | "K" | "Rb" | "Cs");
+        match salt.anion_name {
+            "carbonate" if !alkali => return Ok(vec![oxide(), "CO2".to_string()]),
+            "hydrogen carbonate" | "bicarbonate" => {
+                let carbonate = salt_formula(&salt.cation, salt.cation_charge, "CO3", -2);
+                return Ok(vec![carbonate, "H2O".to_string(), "CO2".to_string()]);
+            }
+            "chlorate" => {
+                let chloride = salt_formula(&salt.cation, salt.cation_charge, "Cl", -1);
+                return Ok(vec![chloride, "O2".to_string()]);
+            }
+            "hydroxide" if !alkali => return Ok(vec![oxide(), "H2O".to_string()]),
+            _ => {}
+        }
+    }
+    if cpd.charge == 0 && cpd.elements.len() == 2 {
+        return Ok(cpd.elements.keys().map(|e| elemental(e)).collect());
+    }
+    Err(format!("cannot predict how {} decomposes", cpd.raw()))
+}
+
+fn activity(element: &str) -> Option<(usize, isize)> {
+    ACTIVITY_SERIES
+        .iter()
+        .position(|(e, _)| *e == element)
+        .map(|i| (i, ACTIVITY_SERIES[i].1))
+}
+
+fn single_displacement(element: &Compound, cpd: &Compound) -> Result<Vec<String>, String> {
+    let symbol = element.elements.keys().next().unwrap().as_str();
+    let no_reaction = |other: &str| {
+        Err(format!(
+            "no reaction: {} is less reactive than {}",
+            elemental(symbol),
+            elemental(other)
+        ))
+    };
+    if let Some(halogen) = HALOGENS.iter().position(|h| *h == symbol) {
+        let salt = salt(cpd)
+            .ok_or_else(|| format!("cannot predict how {} reacts with {}", symbol, cpd.raw()))?;
+        let displaced = HALOGENS
+            .iter()
+            .position(|h| *h == salt.anion)
+            .ok_or_else(|| format!("{} does not contain a halide to displace", cpd.raw()))?;
+        if displaced <= halogen {
+            return no_reaction(salt.anion);
+        }
+        return Ok(vec![
+            salt_formula(&salt.cation, salt.cation_charge, symbol, -1),
+            elemental(salt.anion),
+        ]);
+    }
+    let (rank, charge) =
+        activity(symbol).ok_or_else(|| format!("{} is not in the activity series", symbol))?;
+    if is(cpd, "H2O") {
+        if rank >= WATER_REACTIVE {
+            return Err(format!(
+                "no reaction: {} does not react with cold water",
+                symbol
+            ));
+        }
+        return Ok(vec![
+            salt_formula(symbol, charge, "OH", -1),
+            "H2".to_string(),
+        ]);
+    }
+    let salt = ions(cpd)
+        .ok_or_else(|| format!("cannot predict how {} reacts with {}", symbol, cpd.raw()))?;
+    let (displaced, _) = activity(&salt.cation)
+        .ok_or_else(|| format!("{} is not in the activity series", salt.cation))?;
+    if displaced <= rank {
+        return no_reaction(&salt.cation);
+    }
+    Ok(vec![
+        salt_formula(symbol, charge, salt.anion, salt.anion_charge),
+        elemental(&salt.cation),
+    ])
+}
+
+/// A metal combining with a nonmetal, e.g. Mg + O2 giving MgO.
+fn synthesis(a: &Compound, b: &Compound) -> Result<Vec<String>, String> {
+    let unknown = || format!("cannot predict how {} and {} react", a.raw(), b.raw());
+    let (a, b) = (
+        a.elements.keys().next().unwrap(),
+        b.elements.keys().next().unwrap(),
+    );
+    let (metal, nonmetal) = match (is_metal(a), is_metal(b)) {
+        (true, false) => (a, b),
+        (false, true) => (b, a),
+        _ => return Err(unknown()),
+    };
+    let positive = fixed_state(metal)
+        .or_else(|| activity(metal).map(|(_, charge)| charge))
+        .ok_or_else(unknown)?;
+    let negative = typical_negative_state(nonmetal);
+    if negative == 0 {
+        return Err(unknown());
+    }
+    Ok(vec![salt_formula(metal, positive, nonmetal, negative)])
+}
+
+/// The product of a cation and anion from double displacement, with its phase. Unstable
+/// products break down into water and a gas.
+fn exchange_product(cation: &Salt, anion: &Salt) -> Vec<String> {
+    match (cation.cation.as_str(), anion.anion) {
+        ("H", "OH") => vec!["H2O(l)".to_string()],
+        ("H", "CO3" | "HCO3") => vec!["H2O(l)".to_string(), "CO2(g)".to_string()],
+        ("H", "SO3") => vec!["H2O(l)".to_string(), "SO2(g)".to_string()],
+        ("H", "S") => vec!["H2S(g)".to_string()],
+        ("NH4", "OH") => vec!["NH3(g)".to_string(), "H2O(l)".to_string()],
+        _ => {
+            let salt = Salt {
+                anion: anion.anion,
+                anion_name: anion.anion_name,
+                anion_charge: anion.anion_charge,
+                ..cation.clone()
+            };
+            let phase = if is_soluble(&salt) { "aq" } else { "s" };
+            vec![format!(
+                "{}({})",
+                salt_formula(
+                    &salt.cation,
+                    salt.cation_charge,
+                    salt.anion,
+                    salt.anion_charge
+                ),
+                phase
+            )]
+        }
+    }
+}
+
+fn double_displacement(a: &Compound, b: &Compound) -> Result<Vec<String>, String> {
+    let unknown = || format!("cannot predict how {} and {} react", a.raw(), b.raw());
+    let (x, y) = (ions(a).ok_or_else(unknown)?, ions(b).ok_or_else(unknown)?);
+    if x.cation == y.cation || x.anion == y.anion {
+        return Err(format!(
+            "no reaction: {} and {} share an ion",
+            a.raw(),
+            b.raw()
+        ));
+    }
+    let products = [exchange_product(&x, &y), exchange_product(&y, &x)].concat();
+    if products.iter().all(|p| p.ends_with("(aq)")) {
+        return Err("no reaction: every product stays dissolved".to_string());
+    }
+    Ok(products)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed(input: &str) -> String {
+        complete(parser::parse(input).unwrap()).unwrap().to_string()
+    }
+
+    #[test]
+    fn keeps_the_coefficients_written_on_reactants() {
+        assert_eq!(completed("H2 + 1/2O2 ="), "H2 + 1/2 O2 = H2O");
+        assert_eq!(
+            completed("AgNO3 + NaCl ->"),
+            "AgNO3 + NaCl -> AgCl(s) + NaNO3(aq)"
+        );
+    }
+
+    #[test]
+    fn leaves_equations_with_products_alone() {
+        let eq = parser::parse("H2 + O2 = H2O").unwrap();
+        assert!(!awaits_products(&eq));
+        assert_eq!(completed("H2 + O2 = H2O"), "H2 + O2 = H2O");
+    }
+}
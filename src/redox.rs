@@ -0,0 +1,354 @@
+use crate::isotopes;
+use crate::parser::{to_signed, ChemicalEquation, Compound};
+use num::rational::Ratio;
+use num::{Signed, Zero};
+
+const NONMETALS: [&str; 22] = [
+    "H", "He", "B", "C", "N", "O", "F", "Ne", "Si", "P", "S", "Cl", "Ar", "As", "Se", "Br", "Kr",
+    "Te", "I", "Xe", "At", "Rn",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) struct OxidationState {
+    pub(crate) element: String,
+    pub(crate) state: Ratio<isize>,
+    /// No rule fixed this element and more than one combination of states fits the charge,
+    /// so the most plausible one was picked.
+    pub(crate) assumed: bool,
+    /// The element sits in more than one position in the formula (NH4NO3) or has a
+    /// fractional state (Fe3O4), so this is an average over its atoms.
+    pub(crate) average: bool,
+}
+
+impl OxidationState {
+    pub(crate) fn display_state(&self) -> String {
+        signed(self.state)
+    }
+}
+
+/// Formats an oxidation state with an explicit + sign, as in +3 or -1/2.
+pub(crate) fn signed(state: Ratio<isize>) -> String {
+    if state.is_positive() {
+        format!("+{}", state)
+    } else {
+        state.to_string()
+    }
+}
+
+pub(crate) fn is_metal(element: &str) -> bool {
+    !NONMETALS.contains(&isotopes::element(element))
+}
+
+/// Pauling electronegativity, used to decide which element takes a negative state when the
+/// rules leave more than one element unknown.
+fn electronegativity(element: &str) -> f32 {
+    match isotopes::element(element) {
+        "H" => 2.20,
+        "Li" => 0.98,
+        "Be" => 1.57,
+        "B" => 2.04,
+        "C" => 2.55,
+        "N" => 3.04,
+        "O" => 3.44,
+        "F" => 3.98,
+        "Na" => 0.93,
+        "Mg" => 1.31,
+        "Al" => 1.61,
+        "Si" => 1.90,
+        "P" => 2.19,
+        "S" => 2.58,
+        "Cl" => 3.16,
+        "K" => 0.82,
+        "Ca" => 1.00,
+        "Ti" => 1.54,
+        "V" => 1.63,
+        "Cr" => 1.66,
+        "Mn" => 1.55,
+        "Fe" => 1.83,
+        "Co" => 1.88,
+        "Ni" => 1.91,
+        "Cu" => 1.90,
+        "Zn" => 1.65,
+        "As" => 2.18,
+        "Se" => 2.55,
+        "Br" => 2.96,
+        "Sr" => 0.95,
+        "Ag" => 1.93,
+        "Sn" => 1.96,
+        "Sb" => 2.05,
+        "Te" => 2.10,
+        "I" => 2.66,
+        "Ba" => 0.89,
+        "Pt" => 2.28,
+        "Au" => 2.54,
+        "Hg" => 2.00,
+        "Pb" => 2.33,
+        "Bi" => 2.02,
+        "Xe" => 2.60,
+        other if is_metal(other) => 1.5,
+        _ => 2.5,
+    }
+}
+
+/// States that hold whenever the element is combined with others.
+pub(crate) fn fixed_state(element: &str) -> Option<isize> {
+    match isotopes::element(element) {
+        "F" => Some(-1),
+        "Li" | "Na" | "K" | "Rb" | "Cs" | "Fr" | "Ag" => Some(1),
+        "Be" | "Mg" | "Ca" | "Sr" | "Ba" | "Ra" | "Zn" | "Cd" => Some(2),
+        "Al" | "Ga" | "Sc" => Some(3),
+        _ => None,
+    }
+}
+
+/// Common states of an element, most common first.
+fn common_states(element: &str) -> &'static [isize] {
+    match isotopes::element(element) {
+        "H" => &[1, -1],
+        "B" => &[3],
+        "C" => &[4, -4, 2, -2, 0, 3, -3, 1, -1],
+        "N" => &[-3, 5, 3, 2, 4, 1, -1, -2],
+        "O" => &[-2, -1],
+        "Si" => &[4, -4],
+        "P" => &[5, -3, 3],
+        "S" => &[-2, 6, 4, 2, -1],
+        "Cl" | "Br" | "I" => &[-1, 1, 3, 5, 7],
+        "Se" | "Te" => &[-2, 4, 6],
+        "As" | "Sb" => &[-3, 3, 5],
+        "Fe" => &[3, 2],
+        "Cu" => &[2, 1],
+        "Mn" => &[2, 7, 4, 6, 3],
+        "Cr" => &[3, 6, 2],
+        "Co" | "Ni" => &[2, 3],
+        "Pb" | "Sn" | "Pt" => &[2, 4],
+        "Hg" => &[2, 1],
+        "Au" => &[3, 1],
+        "Ti" => &[4, 3],
+        "V" => &[5, 4, 3, 2],
+        _ => &[2, 3, 1, 4],
+    }
+}
+
+/// Finds the combination of common states for `unknown` that sums to `target`, preferring
+/// combinations where more electronegative elements have lower states, then more common
+/// states. Returns the states and whether more than one combination was possible.
+fn search_states(
+    cpd: &Compound,
+    elements: &[&str],
+    target: Ratio<isize>,
+) -> Option<(Vec<isize>, bool)> {
+    let mut best: Option<((usize, usize), Vec<isize>)> = None;
+    let mut solutions = 0;
+    let mut ixs = vec![0; elements.len()];
+    loop {
+        let states = elements
+            .iter()
+            .zip(&ixs)
+            .map(|(e, i)| common_states(e)[*i])
+            .collect::<Vec<_>>();
+        let sum = elements
+            .iter()
+            .zip(&states)
+            .map(|(e, s)| to_signed(cpd.elements[*e]) * *s)
+            .sum::<Ratio<isize>>();
+        if sum == target {
+            solutions += 1;
+            let mut violations = 0;
+            for a in 0..elements.len() {
+                for b in 0..elements.len() {
+                    if electronegativity(elements[a]) > electronegativity(elements[b])
+                        && states[a] > states[b]
+                    {
+                        violations += 1;
+                    }
+                }
+            }
+            let score = (violations, ixs.iter().sum());
+            let better = match &best {
+                Some((best, _)) => score < *best,
+                None => true,
+            };
+            if better {
+                best = Some((score, states));
+            }
+        }
+        // Step to the next combination, like counting in a mixed-radix number.
+        let mut digit = 0;
+        loop {
+            if digit == elements.len() {
+                return best.map(|(_, states)| (states, solutions > 1));
+            }
+            ixs[digit] += 1;
+            if ixs[digit] < common_states(elements[digit]).len() {
+                break;
+            }
+            ixs[digit] = 0;
+            digit += 1;
+        }
+    }
+}
+
+/// The usual state of an element when it is the most electronegative one present.
+pub(crate) fn typical_negative_state(element: &str) -> isize {
+    match isotopes::element(element) {
+        "F" | "Cl" | "Br" | "I" | "At" | "H" => -1,
+        "O" | "S" | "Se" | "Te" => -2,
+        "N" | "P" | "As" | "Sb" => -3,
+        "C" | "Si" => -4,
+        _ => 0,
+    }
+}
+
+fn assign(
+    states: &mut [OxidationState],
+    unknown: &mut Vec<usize>,
+    i: usize,
+    state: isize,
+    assumed: bool,
+) {
+    states[i].state = Ratio::from_integer(state);
+    states[i].assumed = assumed;
+    unknown.retain(|j| *j != i);
+}
+
+/// Assigns an oxidation state to each element of `cpd`, in the order the elements were written.
+///
+/// Elements on their own are 0. Otherwise F, the alkali and alkaline earth metals, Al, Ga,
+/// Sc, Zn, Cd and Ag take their fixed states, H is +1 (−1 when every other element is a
+/// metal) and O is −2 unless it is the only element left, which covers peroxides and OF2.
+/// A single remaining element takes whatever state makes the states sum to the charge. When
+/// several remain (CuSO4, SCN⁻) their common states are searched for a combination that sums
+/// to the charge; if that fails, the most electronegative are given their usual negative
+/// states in turn.
+pub(crate) fn oxidation_states(cpd: &Compound) -> Vec<OxidationState> {
+    let mut states = cpd
+        .elements
+        .keys()
+        .map(|element| OxidationState {
+            element: element.clone(),
+            state: Ratio::zero(),
+            assumed: false,
+            average: false,
+        })
+        .collect::<Vec<_>>();
+    if states.len() == 1 {
+        states[0].state = Ratio::from_integer(cpd.charge) / to_signed(cpd.elements[0]);
+        states[0].average = !states[0].state.is_integer();
+        return states;
+    }
+    let mut unknown = (0..states.len()).collect::<Vec<_>>();
+    for i in unknown.clone() {
+        if let Some(state) = fixed_state(&states[i].element) {
+            if unknown.len() > 1 {
+                assign(&mut states, &mut unknown, i, state, false);
+            }
+        }
+    }
+    let is = |state: &OxidationState, element: &str| isotopes::element(&state.element) == element;
+    if let Some(i) = unknown.iter().find(|i| is(&states[**i], "H")).copied() {
+        if unknown.len() > 1 {
+            let hydride = cpd
+                .elements
+                .keys()
+                .all(|e| isotopes::element(e) == "H" || is_metal(e));
+            assign(
+                &mut states,
+                &mut unknown,
+                i,
+                if hydride { -1 } else { 1 },
+                false,
+            );
+        }
+    }
+    if let Some(i) = unknown.iter().find(|i| is(&states[**i], "O")).copied() {
+        if unknown.len() > 1 {
+            assign(&mut states, &mut unknown, i, -2, false);
+        }
+    }
+    if unknown.len() > 1 {
+        let known = states
+            .iter()
+            .map(|s| s.state * to_signed(cpd.elements[&s.element]))
+            .sum::<Ratio<isize>>();
+        let elements = unknown
+            .iter()
+            .map(|i| states[*i].element.clone())
+            .collect::<Vec<_>>();
+        let elements = elements.iter().map(String::as_str).collect::<Vec<_>>();
+        let target = Ratio::from_integer(cpd.charge) - known;
+        if let Some((found, ambiguous)) = search_states(cpd, &elements, target) {
+            for (i, state) in unknown.clone().into_iter().zip(found) {
+                assign(&mut states, &mut unknown, i, state, ambiguous);
+            }
+        }
+    }
+    while unknown.len() > 1 {
+        let i = *unknown
+            .iter()
+            .max_by(|a, b| {
+                electronegativity(&states[**a].element)
+                    .total_cmp(&electronegativity(&states[**b].element))
+            })
+            .unwrap();
+        let state = typical_negative_state(&states[i].element);
+        assign(&mut states, &mut unknown, i, state, true);
+    }
+    if let Some(i) = unknown.pop() {
+        let element = &states[i].element;
+        let known = states
+            .iter()
+            .map(|s| s.state * to_signed(cpd.elements[&s.element]))
+            .sum::<Ratio<isize>>();
+        let state = (Ratio::from_integer(cpd.charge) - known) / to_signed(cpd.elements[element]);
+        let positions = cpd
+            .units
+            .iter()
+            .flat_map(|unit| unit.elements())
+            .filter(|(e, _)| e == element)
+            .count();
+        states[i].state = state;
+        states[i].average = !state.is_integer() || positions > 1;
+    }
+    states
+}
+
+/// An element whose oxidation state changes between a reactant and a product.
+#[derive(Debug, Clone)]
+pub(crate) struct RedoxChange {
+    pub(crate) reactant: usize,
+    pub(crate) product: usize,
+    pub(crate) element: String,
+    pub(crate) from: Ratio<isize>,
+    pub(crate) to: Ratio<isize>,
+}
+
+impl RedoxChange {
+    pub(crate) fn is_oxidation(&self) -> bool {
+        self.to > self.from
+    }
+}
+
+/// Every change in oxidation state across `eq`, pairing each reactant with each product that
+/// contains the same element. A species can be both oxidised and reduced (disproportionation).
+pub(crate) fn redox_changes(eq: &ChemicalEquation) -> Vec<RedoxChange> {
+    let states = eq.terms.iter().map(oxidation_states).collect::<Vec<_>>();
+    let mut changes = Vec::new();
+    for reactant in 0..eq.rhs_ix {
+        for product in eq.rhs_ix..eq.terms.len() {
+            for from in &states[reactant] {
+                for to in states[product].iter().filter(|s| s.element == from.element) {
+                    if from.state != to.state {
+                        changes.push(RedoxChange {
+                            reactant,
+                            product,
+                            element: from.element.clone(),
+                            from: from.state,
+                            to: to.state,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    changes
+}
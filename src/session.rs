@@ -0,0 +1,33 @@
+use crate::interface::StoichMode;
+use crate::stoichiometry::{Reactant, SpeciesResult};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub(crate) const DEFAULT_SESSION_PATH: &str = "session.ron";
+
+/// Everything the user has entered into the app, in a form that can be written to disk and
+/// restored later. The balanced equation itself is not stored; it is recomputed from `eq_input`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Session {
+    pub(crate) eq_input: String,
+    pub(crate) stoich_mode: StoichMode,
+    pub(crate) stoich_input_strings: Vec<(String, bool)>,
+    pub(crate) stoich_input_reactants: Vec<Reactant>,
+    pub(crate) outputs: Vec<f32>,
+    #[serde(default)]
+    pub(crate) results: Vec<SpeciesResult>,
+}
+
+impl Session {
+    pub(crate) fn save_to(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| format!("error serializing session: {}", e))?;
+        fs::write(path, contents).map_err(|e| format!("error writing session file: {}", e))
+    }
+    pub(crate) fn load_from(path: impl AsRef<Path>) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("error reading session file: {}", e))?;
+        ron::from_str(&contents).map_err(|e| format!("error parsing session file: {}", e))
+    }
+}
@@ -0,0 +1,49 @@
+use crate::names::{self, Salt};
+use crate::parser::{Compound, Phase};
+
+/// Whether a salt dissolves in water by the usual introductory solubility rules. Acids and
+/// salts of the alkali metals and ammonium always do; otherwise it depends on the anion.
+/// Slightly soluble salts such as CaSO4 and Ca(OH)2 count as insoluble.
+pub(crate) fn is_soluble(salt: &Salt) -> bool {
+    let cation = salt.cation.as_str();
+    let mercury_i = cation == "Hg" && salt.cation_charge == 1;
+    if matches!(cation, "H" | "Li" | "Na" | "K" | "Rb" | "Cs" | "NH4") {
+        return true;
+    }
+    match salt.anion_name {
+        "nitrate" | "acetate" | "chlorate" | "perchlorate" | "permanganate" | "bicarbonate"
+        | "hydrogen carbonate" => true,
+        "chloride" | "bromide" | "iodide" | "thiocyanate" => {
+            !(matches!(cation, "Ag" | "Pb")
+                || mercury_i
+                || (cation == "Cu" && salt.cation_charge == 1))
+        }
+        "fluoride" => !matches!(cation, "Mg" | "Ca" | "Sr" | "Ba" | "Pb"),
+        "sulfate" | "hydrogen sulfate" | "bisulfate" => {
+            !(matches!(cation, "Ca" | "Sr" | "Ba" | "Pb" | "Ag") || mercury_i)
+        }
+        "hydroxide" => matches!(cation, "Sr" | "Ba"),
+        _ => false,
+    }
+}
+
+/// Acids that ionise completely in water.
+pub(crate) fn is_strong_acid(acid: &Salt) -> bool {
+    matches!(
+        acid.anion_name,
+        "chloride" | "bromide" | "iodide" | "nitrate" | "sulfate" | "perchlorate" | "chlorate"
+    )
+}
+
+/// Whether `cpd` is an insoluble solid: marked (s), or with no phase given, a salt the
+/// solubility rules say does not dissolve.
+pub(crate) fn is_precipitate(cpd: &Compound) -> bool {
+    match cpd.phase {
+        Some(Phase::Solid) => true,
+        Some(_) => false,
+        None => cpd
+            .counts()
+            .and_then(|elements| names::salt(&elements))
+            .is_some_and(|salt| !is_soluble(&salt)),
+    }
+}
@@ -1,5 +1,8 @@
 use crate::parser::{ChemicalEquation, Compound};
-#[derive(Clone, Debug, PartialEq)]
+use num::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Reactant {
     Grams(f32),
     Moles(f32),
@@ -8,10 +11,10 @@ pub(crate) enum Reactant {
 }
 
 impl Reactant {
-    fn to_f32_moles(self, cpd: &Compound) -> f32 {
+    fn to_f32_moles(&self, cpd: &Compound) -> f32 {
         match self {
             Reactant::Grams(i) => i / cpd.molar_mass,
-            Reactant::Moles(i) => i,
+            Reactant::Moles(i) => *i,
             Reactant::Excess => f32::MAX,
             Reactant::None => 0.0,
         }
@@ -26,11 +29,37 @@ impl Reactant {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Role {
+    Limiting,
+    Excess,
+    Product,
+}
+
+/// One row of a stoichiometry result. Amounts are in moles; `None` means the amount is
+/// unbounded because the species was marked as sufficient.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct SpeciesResult {
+    pub(crate) species: String,
+    pub(crate) role: Role,
+    pub(crate) molar_mass: f32,
+    pub(crate) initial: Option<f32>,
+    pub(crate) change: f32,
+    pub(crate) final_amount: Option<f32>,
+}
+
+impl SpeciesResult {
+    pub(crate) fn grams(&self, moles: f32) -> f32 {
+        moles * self.molar_mass
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct StoichCalculator {
     pub(crate) eq: ChemicalEquation,
     pub(crate) inputs: Vec<Reactant>,
     pub(crate) outputs: Vec<f32>,
+    pub(crate) results: Vec<SpeciesResult>,
 }
 
 impl StoichCalculator {
@@ -39,36 +68,70 @@ impl StoichCalculator {
             eq,
             inputs,
             outputs: Vec::new(),
+            results: Vec::new(),
         }
     }
     pub(crate) fn product_unknown(&mut self) -> Self {
         let mut outputs = vec![0.0; self.eq.terms.len()];
+        let mut results = Vec::with_capacity(self.eq.terms.len());
         assert_eq!(self.eq.terms.len(), self.inputs.len());
         let mut limiting_unit_amt = f32::MAX;
+        let mut limiting_ix = None;
         for (i, cpd) in self.eq.terms[0..self.eq.rhs_ix].iter().enumerate() {
             let product_produced =
-                self.inputs[i].clone().to_f32_moles(&cpd) / cpd.coefficient as f32;
+                self.inputs[i].to_f32_moles(cpd) / cpd.coefficient.to_f32().unwrap();
             if product_produced < limiting_unit_amt {
                 limiting_unit_amt = product_produced;
+                limiting_ix = Some(i);
             }
         }
         for (i, cpd) in self.eq.terms.iter().enumerate() {
+            let coefficient = cpd.coefficient.to_f32().unwrap();
             outputs[i] = match self.inputs[i] {
                 Reactant::Grams(grams) => {
-                    (grams / cpd.molar_mass) - limiting_unit_amt * cpd.coefficient as f32
+                    (grams / cpd.molar_mass) - limiting_unit_amt * coefficient
                 }
-                Reactant::Moles(moles) => moles - limiting_unit_amt * cpd.coefficient as f32,
-                Reactant::Excess => limiting_unit_amt * cpd.coefficient as f32 * -1.0,
-                Reactant::None => limiting_unit_amt * cpd.coefficient as f32,
-            }
+                Reactant::Moles(moles) => moles - limiting_unit_amt * coefficient,
+                Reactant::Excess => -(limiting_unit_amt * coefficient),
+                Reactant::None => limiting_unit_amt * coefficient,
+            };
+            let used = limiting_unit_amt * coefficient;
+            let (role, initial, change) = if i >= self.eq.rhs_ix {
+                (Role::Product, Some(0.0), used)
+            } else {
+                let role = if limiting_ix == Some(i) {
+                    Role::Limiting
+                } else {
+                    Role::Excess
+                };
+                let initial = match self.inputs[i] {
+                    Reactant::Excess => None,
+                    ref reactant => Some(reactant.to_f32_moles(cpd)),
+                };
+                (role, initial, -used)
+            };
+            results.push(SpeciesResult {
+                species: cpd.raw(),
+                role,
+                molar_mass: cpd.molar_mass,
+                initial,
+                change,
+                final_amount: initial.map(|initial| initial + change),
+            });
         }
         self.outputs = outputs;
+        self.results = results;
         self.clone()
     }
 }
 
 pub(crate) fn str_to_molar_mass(element: &str) -> f32 {
-    match element {
+    element_molar_mass(element).expect("not an element")
+}
+
+/// Standard atomic weight of an element, or `None` if `element` is not an element symbol.
+pub(crate) fn element_molar_mass(element: &str) -> Option<f32> {
+    Some(match element {
         "H" => 1.00797,
         "He" => 4.00260,
         "Li" => 6.941,
@@ -187,6 +250,6 @@ pub(crate) fn str_to_molar_mass(element: &str) -> f32 {
         "Lv" => 293.0,
         "Ts" => 294.0,
         "Og" => 294.0,
-        _ => panic!("not an element"),
-    }
+        _ => return None,
+    })
 }
@@ -0,0 +1,225 @@
+use crate::parser::{parse_compound, ChemicalEquation, Compound, Phase, Side};
+use crate::stoichiometry::StoichCalculator;
+use num::ToPrimitive;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+/// Standard-state data at 298.15 K: ΔHf° in kJ/mol, S° in J/(mol·K) and ΔGf° in kJ/mol.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ThermoData {
+    pub(crate) enthalpy: f64,
+    pub(crate) entropy: f64,
+    pub(crate) gibbs: f64,
+}
+
+/// Data entered by the user for species missing from the built-in table, keyed by the
+/// compound as written without a coefficient (see `Compound::raw`).
+pub(crate) type UserData = HashMap<String, ThermoData>;
+
+/// Built-in table. Species are matched on composition, charge and phase, so the formula can be
+/// written in any order. When a compound is given without a state symbol the first entry for
+/// it is used, so the usual standard state of each substance comes first.
+const TABLE: &[(&str, Phase, f64, f64, f64)] = &[
+    ("H2", Phase::Gas, 0.0, 130.7, 0.0),
+    ("O2", Phase::Gas, 0.0, 205.2, 0.0),
+    ("N2", Phase::Gas, 0.0, 191.6, 0.0),
+    ("Cl2", Phase::Gas, 0.0, 223.1, 0.0),
+    ("F2", Phase::Gas, 0.0, 202.8, 0.0),
+    ("Br2", Phase::Liquid, 0.0, 152.2, 0.0),
+    ("I2", Phase::Solid, 0.0, 116.1, 0.0),
+    ("O3", Phase::Gas, 142.7, 238.9, 163.2),
+    ("C", Phase::Solid, 0.0, 5.7, 0.0),
+    ("S", Phase::Solid, 0.0, 32.1, 0.0),
+    ("Na", Phase::Solid, 0.0, 51.3, 0.0),
+    ("Mg", Phase::Solid, 0.0, 32.7, 0.0),
+    ("Al", Phase::Solid, 0.0, 28.3, 0.0),
+    ("Ca", Phase::Solid, 0.0, 41.6, 0.0),
+    ("Fe", Phase::Solid, 0.0, 27.3, 0.0),
+    ("Cu", Phase::Solid, 0.0, 33.2, 0.0),
+    ("Zn", Phase::Solid, 0.0, 41.6, 0.0),
+    ("Ag", Phase::Solid, 0.0, 42.6, 0.0),
+    ("H2O", Phase::Liquid, -285.8, 69.9, -237.1),
+    ("H2O", Phase::Gas, -241.8, 188.8, -228.6),
+    ("H2O2", Phase::Liquid, -187.8, 109.6, -120.4),
+    ("CO", Phase::Gas, -110.5, 197.7, -137.2),
+    ("CO2", Phase::Gas, -393.5, 213.8, -394.4),
+    ("CH4", Phase::Gas, -74.6, 186.3, -50.5),
+    ("C2H2", Phase::Gas, 227.4, 200.9, 209.9),
+    ("C2H4", Phase::Gas, 52.4, 219.3, 68.4),
+    ("C2H6", Phase::Gas, -84.0, 229.2, -32.0),
+    ("C3H8", Phase::Gas, -103.8, 270.3, -23.4),
+    ("C6H6", Phase::Liquid, 49.1, 173.4, 124.5),
+    ("C8H18", Phase::Liquid, -250.1, 361.1, 6.4),
+    ("CH3OH", Phase::Liquid, -239.2, 126.8, -166.6),
+    ("C2H5OH", Phase::Liquid, -277.6, 160.7, -174.8),
+    ("CH3COOH", Phase::Liquid, -484.3, 159.8, -389.9),
+    ("C6H12O6", Phase::Solid, -1273.3, 209.2, -910.4),
+    ("C12H22O11", Phase::Solid, -2226.1, 360.2, -1544.7),
+    ("NH3", Phase::Gas, -45.9, 192.8, -16.4),
+    ("NO", Phase::Gas, 91.3, 210.8, 87.6),
+    ("NO2", Phase::Gas, 33.2, 240.1, 51.3),
+    ("N2O", Phase::Gas, 81.6, 220.0, 103.7),
+    ("N2O4", Phase::Gas, 11.1, 304.4, 99.8),
+    ("HNO3", Phase::Liquid, -174.1, 155.6, -80.7),
+    ("SO2", Phase::Gas, -296.8, 248.2, -300.1),
+    ("SO3", Phase::Gas, -395.7, 256.8, -371.1),
+    ("H2S", Phase::Gas, -20.6, 205.8, -33.4),
+    ("H2SO4", Phase::Liquid, -814.0, 156.9, -690.0),
+    ("HF", Phase::Gas, -273.3, 173.8, -275.4),
+    ("HCl", Phase::Gas, -92.3, 186.9, -95.3),
+    ("HBr", Phase::Gas, -36.3, 198.7, -53.4),
+    ("HI", Phase::Gas, 26.5, 206.6, 1.7),
+    ("NaCl", Phase::Solid, -411.2, 72.1, -384.1),
+    ("NaOH", Phase::Solid, -425.8, 64.4, -379.7),
+    ("KCl", Phase::Solid, -436.5, 82.6, -408.5),
+    ("KClO3", Phase::Solid, -397.7, 143.1, -296.3),
+    ("AgCl", Phase::Solid, -127.0, 96.3, -109.8),
+    ("NH4Cl", Phase::Solid, -314.4, 94.6, -202.9),
+    ("NH4NO3", Phase::Solid, -365.6, 151.1, -183.9),
+    ("MgO", Phase::Solid, -601.6, 27.0, -569.3),
+    ("CaO", Phase::Solid, -634.9, 38.1, -603.3),
+    ("CaCO3", Phase::Solid, -1207.6, 91.7, -1129.1),
+    ("Al2O3", Phase::Solid, -1675.7, 50.9, -1582.3),
+    ("SiO2", Phase::Solid, -910.7, 41.5, -856.3),
+    ("Fe2O3", Phase::Solid, -824.2, 87.4, -742.2),
+    ("Fe3O4", Phase::Solid, -1118.4, 146.4, -1015.4),
+    ("H^+", Phase::Aqueous, 0.0, 0.0, 0.0),
+    ("OH^-", Phase::Aqueous, -230.0, -10.8, -157.2),
+    ("Na^+", Phase::Aqueous, -240.1, 59.0, -261.9),
+    ("K^+", Phase::Aqueous, -252.4, 102.5, -283.3),
+    ("Ag^+", Phase::Aqueous, 105.6, 72.7, 77.1),
+    ("NH4^+", Phase::Aqueous, -132.5, 113.4, -79.3),
+    ("Ca^2+", Phase::Aqueous, -542.8, -53.1, -553.6),
+    ("Cu^2+", Phase::Aqueous, 64.8, -99.6, 65.5),
+    ("Zn^2+", Phase::Aqueous, -153.9, -112.1, -147.1),
+    ("Fe^2+", Phase::Aqueous, -89.1, -137.7, -78.9),
+    ("Fe^3+", Phase::Aqueous, -48.5, -315.9, -4.7),
+    ("Cl^-", Phase::Aqueous, -167.2, 56.5, -131.2),
+    ("NO3^-", Phase::Aqueous, -205.0, 146.4, -108.7),
+    ("CO3^2-", Phase::Aqueous, -677.1, -56.9, -527.8),
+    ("SO4^2-", Phase::Aqueous, -909.3, 20.1, -744.5),
+];
+
+/// The built-in table with its formulas parsed, which is done once on first use.
+fn table() -> &'static [(Compound, Phase, ThermoData)] {
+    static PARSED: OnceLock<Vec<(Compound, Phase, ThermoData)>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        TABLE
+            .iter()
+            .map(|(formula, phase, enthalpy, entropy, gibbs)| {
+                let data = ThermoData {
+                    enthalpy: *enthalpy,
+                    entropy: *entropy,
+                    gibbs: *gibbs,
+                };
+                (parse_compound(formula).unwrap(), *phase, data)
+            })
+            .collect()
+    })
+}
+
+/// Looks a compound up, preferring data supplied by the user over the built-in table.
+pub(crate) fn lookup(cpd: &Compound, user: &UserData) -> Option<ThermoData> {
+    if let Some(data) = user.get(&cpd.raw()) {
+        return Some(*data);
+    }
+    table()
+        .iter()
+        .filter(|(_, phase, _)| cpd.phase.map_or(true, |p| p == *phase))
+        .find(|(entry, ..)| entry.elements == cpd.elements && entry.charge == cpd.charge)
+        .map(|(.., data)| *data)
+}
+
+/// Indices of the terms of `eq` that have no thermodynamic data.
+pub(crate) fn missing(eq: &ChemicalEquation, user: &UserData) -> Vec<usize> {
+    (0..eq.terms.len())
+        .filter(|i| lookup(&eq.terms[*i], user).is_none())
+        .collect()
+}
+
+/// Standard changes for the reaction as balanced: ΔH° and ΔG° in kJ, ΔS° in J/K.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReactionThermo {
+    pub(crate) delta_h: f64,
+    pub(crate) delta_s: f64,
+    pub(crate) delta_g: f64,
+}
+
+impl ReactionThermo {
+    /// ΔG = ΔH − TΔS, assuming ΔH° and ΔS° do not vary with temperature.
+    pub(crate) fn delta_g_at(&self, kelvin: f64) -> f64 {
+        self.delta_h - kelvin * self.delta_s / 1000.0
+    }
+    /// Heat absorbed by the reaction, in kJ, for the amounts consumed in a stoichiometry run.
+    /// Negative when heat is released.
+    pub(crate) fn heat(&self, calculator: &StoichCalculator) -> Option<f64> {
+        calculator
+            .eq
+            .terms
+            .iter()
+            .zip(&calculator.results)
+            .map(|(cpd, result)| result.change.abs() as f64 / cpd.coefficient.to_f64().unwrap())
+            .find(|extent| extent.is_finite())
+            .map(|extent| self.delta_h * extent)
+    }
+}
+
+pub(crate) fn reaction(eq: &ChemicalEquation, user: &UserData) -> Result<ReactionThermo, String> {
+    let missing = missing(eq, user);
+    if !missing.is_empty() {
+        return Err(format!(
+            "no thermodynamic data for {}",
+            missing
+                .iter()
+                .map(|i| eq.terms[*i].raw())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    let mut thermo = ReactionThermo {
+        delta_h: 0.0,
+        delta_s: 0.0,
+        delta_g: 0.0,
+    };
+    for cpd in &eq.terms {
+        let data = lookup(cpd, user).unwrap();
+        let coefficient = cpd.coefficient.to_f64().unwrap();
+        let nu = match cpd.side {
+            Side::LHS => -coefficient,
+            Side::RHS => coefficient,
+        };
+        thermo.delta_h += nu * data.enthalpy;
+        thermo.delta_s += nu * data.entropy;
+        thermo.delta_g += nu * data.gibbs;
+    }
+    Ok(thermo)
+}
+
+impl Display for ReactionThermo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "ΔH° = {:.1} kJ", self.delta_h)?;
+        writeln!(f, "ΔS° = {:.1} J/K", self.delta_s)?;
+        writeln!(f, "ΔG° = {:.1} kJ", self.delta_g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_table_formula_parses() {
+        assert_eq!(table().len(), TABLE.len());
+    }
+
+    #[test]
+    fn looks_up_by_composition_and_phase() {
+        let user = UserData::new();
+        let water = lookup(&parse_compound("H2O(l)").unwrap(), &user).unwrap();
+        assert_eq!(water.enthalpy, -285.8);
+        let steam = lookup(&parse_compound("H2O(g)").unwrap(), &user).unwrap();
+        assert_eq!(steam.enthalpy, -241.8);
+        assert!(lookup(&parse_compound("XeF6").unwrap(), &user).is_none());
+    }
+}